@@ -25,11 +25,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .build()
         .await?;
 
-    println!("{:?}", &response);
+    println!("{:?}", response);
 
     let characters = CharacterQuery::execute(&client).await?;
 
-    println!("{:?}", &characters);
+    println!("{:?}", characters);
 
     Ok(())
 }
@@ -1,7 +1,5 @@
 use auraxis::realtime::event::EventNames;
-use auraxis::realtime::subscription::{
-    CharacterSubscription, EventSubscription, SubscriptionSettings, WorldSubscription,
-};
+use auraxis::realtime::subscription::SubscriptionSettings;
 use auraxis::realtime::{
     client::{RealtimeClient, RealtimeClientConfig},
     event::Event,
@@ -21,17 +19,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
         ..RealtimeClientConfig::default()
     };
 
-    let subscription = SubscriptionSettings {
-        event_names: Some(EventSubscription::Ids(vec![EventNames::PlayerLogin])),
-        characters: Some(CharacterSubscription::All),
-        worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
-        logical_and_characters_with_worlds: Some(true),
-        ..SubscriptionSettings::default()
-    };
+    let subscription = SubscriptionSettings::builder()
+        .events([EventNames::PlayerLogin])
+        .all_characters()
+        .worlds([WorldID::Emerald])
+        .logical_and(true)
+        .build()?;
 
     let mut client = RealtimeClient::new(config);
 
-    client.subscribe(subscription);
+    let _subscription = client.subscribe(subscription);
 
     let mut events = client.connect().await?;
 
@@ -41,7 +38,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 println!("Player {} logged in", player.character_id);
             }
             _ => {
-                println!("{:?}", &event);
+                println!("{:?}", event);
             }
         }
     }
@@ -54,7 +54,7 @@ impl ApiClient {
     }
 
     pub fn get(&self, collection: impl Into<String> + Clone) -> CensusRequestBuilder {
-        let url = format!("{}/get/{}", &self.base_url, &self.environment);
+        let url = format!("{}/get/{}", self.base_url, self.environment);
 
         let url = format!("{}/{}", url, collection.clone().into());
 
@@ -62,6 +62,6 @@ impl ApiClient {
     }
 
     pub async fn count(&self, _collection: CensusCollection) {
-        let _url = format!("{}/count/{}", &self.base_url, &self.environment);
+        let _url = format!("{}/count/{}", self.base_url, self.environment);
     }
 }
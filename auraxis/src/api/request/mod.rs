@@ -29,9 +29,18 @@ impl IntoFuture for CensusRequest {
                 request = request.query(&self.query_params);
             }
 
-            let response = request.send().await?;
-
-            CensusResponse::from_response(response).await
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(err) if err.is_timeout() => {
+                    return Err(AuraxisError::Timeout {
+                        operation: format!("{} request", self.collection),
+                    });
+                }
+                // The url of the request contains the service ID.
+                Err(err) => return Err(err.without_url().into()),
+            };
+
+            CensusResponse::from_response(response, &self.collection).await
         }
     }
 }
@@ -2,6 +2,7 @@ use reqwest::Response;
 use serde::{de::IgnoredAny, de::Visitor, Deserialize};
 
 use crate::AuraxisError;
+use crate::error::{body_excerpt, redact_service_id};
 
 #[derive(Debug)]
 pub struct CensusResponse {
@@ -57,14 +58,87 @@ impl<'de> Visitor<'de> for CensusResponseVisitor {
 }
 
 impl CensusResponse {
-    pub async fn from_response(response: Response) -> Result<Self, AuraxisError> {
-        Ok(response.json::<CensusResponse>().await?)
+    pub async fn from_response(response: Response, collection: &str) -> Result<Self, AuraxisError> {
+        let url = redact_service_id(response.url().as_str());
+        let status = response.status().as_u16();
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(err) if err.is_timeout() => {
+                return Err(AuraxisError::Timeout {
+                    operation: format!("{collection} response"),
+                });
+            }
+            Err(err) => return Err(err.without_url().into()),
+        };
+
+        Self::parse(collection, url, status, &body)
+    }
+
+    /// Turn a Census reply into a response or the error Census reported.
+    fn parse(collection: &str, url: String, status: u16, body: &str) -> Result<Self, AuraxisError> {
+        let invalid_response = |url: String| AuraxisError::InvalidResponse {
+            collection: collection.to_string(),
+            url,
+            body_excerpt: body_excerpt(body, MAX_BODY_EXCERPT_CHARS),
+        };
+
+        if status == 429 {
+            return Err(AuraxisError::RateLimited {
+                collection: collection.to_string(),
+                url,
+            });
+        }
+
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+            return Err(invalid_response(url));
+        };
+
+        let error_code = value
+            .get("errorCode")
+            .and_then(serde_json::Value::as_str)
+            .filter(|code| *code != "ok");
+        let error = value.get("error").and_then(serde_json::Value::as_str);
+
+        if let Some(message) = error
+            && message.starts_with("Missing Service ID")
+        {
+            return Err(AuraxisError::MissingServiceId { url });
+        }
+
+        if let Some(message) = error
+            && message.to_lowercase().contains("rate limit")
+        {
+            return Err(AuraxisError::RateLimited {
+                collection: collection.to_string(),
+                url,
+            });
+        }
+
+        if error_code.is_some() || error.is_some() {
+            return Err(AuraxisError::CensusError {
+                collection: collection.to_string(),
+                url,
+                code: error_code.unwrap_or("ERROR").to_string(),
+                message: value
+                    .get("errorMessage")
+                    .and_then(serde_json::Value::as_str)
+                    .or(error)
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+
+        serde_json::from_str(body).map_err(|_| invalid_response(url))
     }
 }
 
+/// How much of an unexpected response body is kept in the error.
+const MAX_BODY_EXCERPT_CHARS: usize = 200;
+
 #[cfg(test)]
 mod tests {
     use super::CensusResponse;
+    use crate::AuraxisError;
 
     #[test]
     fn ignores_extra_metadata_keys() {
@@ -81,4 +155,55 @@ mod tests {
         assert_eq!(response.count, 1);
         assert_eq!(response.items.len(), 1);
     }
+
+    #[test]
+    fn reports_census_errors() {
+        let parse = |status: u16, body: &str| {
+            CensusResponse::parse("character", "https://example.com".to_string(), status, body)
+        };
+
+        assert!(matches!(
+            parse(
+                200,
+                r#"{"error": "Missing Service ID.  A valid Service ID is required."}"#
+            ),
+            Err(AuraxisError::MissingServiceId { .. })
+        ));
+        assert!(matches!(
+            parse(429, ""),
+            Err(AuraxisError::RateLimited { collection, .. }) if collection == "character"
+        ));
+        assert!(matches!(
+            parse(200, r#"{"errorCode": "SERVER_ERROR", "errorMessage": "INVALID_SEARCH_TERM"}"#),
+            Err(AuraxisError::CensusError { code, message, .. })
+                if code == "SERVER_ERROR" && message == "INVALID_SEARCH_TERM"
+        ));
+        assert!(matches!(
+            parse(503, "<html>Service Unavailable</html>"),
+            Err(AuraxisError::InvalidResponse { body_excerpt, .. })
+                if body_excerpt == "<html>Service Unavailable</html>"
+        ));
+        assert!(matches!(
+            parse(200, r#"{"character_list": [], "returned": 0}"#),
+            Ok(CensusResponse { count: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn parses_successful_responses() {
+        let response = CensusResponse::parse(
+            "character",
+            "https://example.com".to_string(),
+            200,
+            r#"{
+                "character_list": [{"character_id": "1"}, {"character_id": "2"}],
+                "returned": 2,
+                "timing": {"query": "1.23"}
+            }"#,
+        )
+        .expect("response should parse");
+
+        assert_eq!(response.count, 2);
+        assert_eq!(response.items[1]["character_id"], "2");
+    }
 }
@@ -1,47 +1,139 @@
-use num_enum::{IntoPrimitive, TryFromPrimitive};
+use crate::ZoneID;
+
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
-#[repr(i16)]
-#[derive(
-    Serialize, Deserialize, Copy, Clone, Eq, Debug, PartialEq, Hash, TryFromPrimitive, IntoPrimitive,
-)]
-#[cfg_attr(
-    feature = "strum",
-    derive(strum::Display, strum::EnumIter, strum::VariantNames, strum::FromRepr)
-)]
-pub enum Loadout {
-    Unknown = 0,
-    NCInfiltrator = 1,
-    NCLightAssault = 3,
-    NCMedic = 4,
-    NCEngineer = 5,
-    NCHeavyAssault = 6,
-    NCMAX = 7,
-    TRInfiltrator = 8,
-    TRLightAssault = 10,
-    TRMedic = 11,
-    TREngineer = 12,
-    TRHeavyAssault = 13,
-    TRMAX = 14,
-    VSInfiltrator = 15,
-    VSLightAssault = 17,
-    VSMedic = 18,
-    VSEngineer = 19,
-    VSHeavyAssault = 20,
-    VSMAX = 21,
-    NSInfiltrator = 28,
-    NSLightAssault = 29,
-    NSMedic = 30,
-    NSEngineer = 31,
-    NSHeavyAssault = 32,
-    NSMAX = 45,
+/// The id of a `Loadout`, `Faction` or `WorldID` this crate does not know.
+///
+/// Only built from ids that have no variant, so an `Unknown` never stands in
+/// for a known one: `WorldID::from(17)` is always `WorldID::Emerald`. The
+/// default is id 0, which none of them define.
+#[derive(Serialize, Copy, Clone, Eq, Debug, Default, PartialEq, Hash)]
+pub struct UnknownId(u16);
+
+impl From<UnknownId> for u16 {
+    fn from(id: UnknownId) -> Self {
+        id.0
+    }
+}
+
+impl std::fmt::Display for UnknownId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// How a `Loadout`, `Faction` or `WorldID` is serialized: the variant name, or
+/// `{"Unknown": id}` for unknown ids. A bare `"Unknown"` is the id 0 that
+/// `Loadout` and `Faction` used to have a variant for.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IdRepr {
+    Name(String),
+    Unknown {
+        #[serde(rename = "Unknown")]
+        id: u16,
+    },
+}
+
+/// Declares an id enum with an `Unknown(UnknownId)` fallback, along with its
+/// conversions from and to the numeric id.
+macro_rules! id_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $id:literal,)+
+            $(#[$unknown_meta:meta])*
+            Unknown(UnknownId),
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(u16)]
+        #[derive(Serialize, Copy, Clone, Eq, Debug, PartialEq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($variant = $id,)+
+            $(#[$unknown_meta])*
+            Unknown(UnknownId),
+        }
+
+        impl From<u16> for $name {
+            fn from(id: u16) -> Self {
+                match id {
+                    $($id => $name::$variant,)+
+                    id => $name::Unknown(UnknownId(id)),
+                }
+            }
+        }
+
+        impl From<$name> for u16 {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $id,)+
+                    $name::Unknown(id) => id.0,
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                match IdRepr::deserialize(deserializer)? {
+                    IdRepr::Name(name) => match name.as_str() {
+                        $(stringify!($variant) => Ok($name::$variant),)+
+                        "Unknown" => Ok($name::from(0)),
+                        name => Err(serde::de::Error::unknown_variant(
+                            name,
+                            &[$(stringify!($variant),)+ "Unknown"],
+                        )),
+                    },
+                    IdRepr::Unknown { id } => Ok($name::from(id)),
+                }
+            }
+        }
+    };
+}
+
+id_enum! {
+    #[cfg_attr(
+        feature = "strum",
+        derive(strum::Display, strum::EnumIter, strum::VariantNames, strum::FromRepr)
+    )]
+    pub enum Loadout {
+        NCInfiltrator = 1,
+        NCLightAssault = 3,
+        NCMedic = 4,
+        NCEngineer = 5,
+        NCHeavyAssault = 6,
+        NCMAX = 7,
+        TRInfiltrator = 8,
+        TRLightAssault = 10,
+        TRMedic = 11,
+        TREngineer = 12,
+        TRHeavyAssault = 13,
+        TRMAX = 14,
+        VSInfiltrator = 15,
+        VSLightAssault = 17,
+        VSMedic = 18,
+        VSEngineer = 19,
+        VSHeavyAssault = 20,
+        VSMAX = 21,
+        NSInfiltrator = 28,
+        NSLightAssault = 29,
+        NSMedic = 30,
+        NSEngineer = 31,
+        NSHeavyAssault = 32,
+        NSMAX = 45,
+        /// A loadout this crate does not know, holding its id.
+        Unknown(UnknownId),
+    }
 }
 
 impl Loadout {
     pub fn get_faction(&self) -> Faction {
         match self {
-            Loadout::Unknown => Faction::Unknown,
             Loadout::NCInfiltrator => Faction::NC,
             Loadout::NCLightAssault => Faction::NC,
             Loadout::NCMedic => Faction::NC,
@@ -66,6 +158,7 @@ impl Loadout {
             Loadout::NSEngineer => Faction::NS,
             Loadout::NSHeavyAssault => Faction::NS,
             Loadout::NSMAX => Faction::NS,
+            Loadout::Unknown(_) => Faction::from(0),
         }
     }
 }
@@ -74,66 +167,130 @@ impl FromStr for Loadout {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let id = i16::from_str(s)?;
-        let loadout = Self::try_from(id)?;
+        let id = u16::from_str(s)?;
 
-        Ok(loadout)
+        Ok(Self::from(id))
     }
 }
 
-#[repr(i16)]
-#[derive(
-    Serialize, Deserialize, Copy, Clone, Eq, Debug, PartialEq, Hash, TryFromPrimitive, IntoPrimitive,
-)]
-#[cfg_attr(
-    feature = "strum",
-    derive(strum::Display, strum::EnumIter, strum::VariantNames, strum::FromRepr)
-)]
-pub enum Faction {
-    Unknown = 0,
-    VS = 1,
-    NC = 2,
-    TR = 3,
-    NS = 4,
+id_enum! {
+    #[cfg_attr(
+        feature = "strum",
+        derive(strum::Display, strum::EnumIter, strum::VariantNames, strum::FromRepr)
+    )]
+    pub enum Faction {
+        VS = 1,
+        NC = 2,
+        TR = 3,
+        NS = 4,
+        /// No faction (id 0) or a faction this crate does not know, holding its id.
+        Unknown(UnknownId),
+    }
 }
 
 impl FromStr for Faction {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let id = i16::from_str(s)?;
-        let faction = Self::try_from(id)?;
+        let id = u16::from_str(s)?;
+
+        Ok(Self::from(id))
+    }
+}
 
-        Ok(faction)
+id_enum! {
+    #[cfg_attr(
+        feature = "strum",
+        derive(strum::Display, strum::EnumIter, strum::VariantNames, strum::FromRepr)
+    )]
+    pub enum WorldID {
+        Jaeger = 19,
+        Briggs = 25,
+        Miller = 10,
+        Cobalt = 13,
+        Connery = 1,
+        Emerald = 17,
+        Soltech = 40,
+        Genudine = 1000,
+        Palos = 1001,
+        Crux = 1002,
+        Searhus = 1003,
+        Xelas = 1004,
+        Ceres = 2000,
+        Lithcorp = 2001,
+        Rashnu = 2002,
+        /// A world this crate does not know, holding its id.
+        Unknown(UnknownId),
     }
 }
 
-#[repr(i16)]
-#[derive(
-    Serialize, Deserialize, Copy, Clone, Eq, Debug, PartialEq, Hash, TryFromPrimitive, IntoPrimitive,
-)]
-#[cfg_attr(
-    feature = "strum",
-    derive(strum::Display, strum::EnumIter, strum::VariantNames, strum::FromRepr)
-)]
-pub enum WorldID {
-    Jaeger = 19,
-    Briggs = 25,
-    Miller = 10,
-    Cobalt = 13,
-    Connery = 1,
-    Emerald = 17,
-    Soltech = 40,
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, Debug, PartialEq, Hash)]
+pub enum Platform {
+    PC,
+    PS4,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, Debug, PartialEq, Hash)]
+pub enum Region {
+    NorthAmerica,
+    Europe,
+    Oceania,
+    Asia,
+}
+
+impl WorldID {
+    /// The platform of the world, `None` for unknown worlds.
+    pub fn platform(&self) -> Option<Platform> {
+        match self {
+            WorldID::Jaeger
+            | WorldID::Briggs
+            | WorldID::Miller
+            | WorldID::Cobalt
+            | WorldID::Connery
+            | WorldID::Emerald
+            | WorldID::Soltech => Some(Platform::PC),
+            WorldID::Genudine
+            | WorldID::Palos
+            | WorldID::Crux
+            | WorldID::Searhus
+            | WorldID::Xelas
+            | WorldID::Ceres
+            | WorldID::Lithcorp
+            | WorldID::Rashnu => Some(Platform::PS4),
+            WorldID::Unknown(_) => None,
+        }
+    }
+
+    /// Where the world is hosted, `None` for unknown worlds.
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            WorldID::Jaeger
+            | WorldID::Connery
+            | WorldID::Emerald
+            | WorldID::Genudine
+            | WorldID::Palos
+            | WorldID::Crux
+            | WorldID::Searhus
+            | WorldID::Xelas => Some(Region::NorthAmerica),
+            WorldID::Miller
+            | WorldID::Cobalt
+            | WorldID::Ceres
+            | WorldID::Lithcorp
+            | WorldID::Rashnu => Some(Region::Europe),
+            WorldID::Briggs => Some(Region::Oceania),
+            WorldID::Soltech => Some(Region::Asia),
+            WorldID::Unknown(_) => None,
+        }
+    }
 }
 
 impl FromStr for WorldID {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let id = i16::from_str(s)?;
-        let world = Self::try_from(id)?;
+        let id = u16::from_str(s)?;
 
-        Ok(world)
+        Ok(Self::from(id))
     }
 }
 
@@ -148,6 +305,269 @@ impl std::fmt::Display for WorldID {
             WorldID::Connery => write!(f, "Connery"),
             WorldID::Emerald => write!(f, "Emerald"),
             WorldID::Soltech => write!(f, "Soltech"),
+            WorldID::Genudine => write!(f, "Genudine"),
+            WorldID::Palos => write!(f, "Palos"),
+            WorldID::Crux => write!(f, "Crux"),
+            WorldID::Searhus => write!(f, "Searhus"),
+            WorldID::Xelas => write!(f, "Xelas"),
+            WorldID::Ceres => write!(f, "Ceres"),
+            WorldID::Lithcorp => write!(f, "Lithcorp"),
+            WorldID::Rashnu => write!(f, "Rashnu"),
+            WorldID::Unknown(id) => write!(f, "Unknown({id})"),
+        }
+    }
+}
+
+/// The continent a zone is based on, decoded from the lower 16 bits of a
+/// [`ZoneID`].
+#[derive(Copy, Clone, Eq, Debug, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Continent {
+    Indar,
+    Hossin,
+    Amerish,
+    Esamir,
+    /// The outfit wars arena.
+    Nexus,
+    Koltyr,
+    /// The VR training zone of any faction.
+    VR,
+    Oshur,
+    Desolation,
+    Sanctuary,
+    /// The tutorial new characters start in.
+    Tutorial,
+    /// A zone definition this crate does not know, holding its id.
+    Unknown(u16),
+}
+
+impl From<u16> for Continent {
+    fn from(definition: u16) -> Self {
+        match definition {
+            2 => Continent::Indar,
+            4 => Continent::Hossin,
+            6 => Continent::Amerish,
+            8 => Continent::Esamir,
+            10 => Continent::Nexus,
+            14 => Continent::Koltyr,
+            96..=98 => Continent::VR,
+            344 => Continent::Oshur,
+            361 => Continent::Desolation,
+            362 => Continent::Sanctuary,
+            364 => Continent::Tutorial,
+            definition => Continent::Unknown(definition),
+        }
+    }
+}
+
+impl std::fmt::Display for Continent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Continent::Indar => write!(f, "Indar"),
+            Continent::Hossin => write!(f, "Hossin"),
+            Continent::Amerish => write!(f, "Amerish"),
+            Continent::Esamir => write!(f, "Esamir"),
+            Continent::Nexus => write!(f, "Nexus"),
+            Continent::Koltyr => write!(f, "Koltyr"),
+            Continent::VR => write!(f, "VR"),
+            Continent::Oshur => write!(f, "Oshur"),
+            Continent::Desolation => write!(f, "Desolation"),
+            Continent::Sanctuary => write!(f, "Sanctuary"),
+            Continent::Tutorial => write!(f, "Tutorial"),
+            Continent::Unknown(definition) => write!(f, "Unknown({definition})"),
+        }
+    }
+}
+
+/// A [`ZoneID`] split into its zone definition and instance.
+///
+/// Census packs the instance of instanced zones, such as Koltyr, Desolation
+/// or outfit wars matches, into the upper 16 bits of the zone id. The
+/// instance is 0 for the permanent continents. Serializes as the packed id
+/// string sent by Census, and deserializes from the id as a string or number.
+#[derive(Copy, Clone, Eq, Debug, PartialEq, Hash)]
+pub struct Zone {
+    pub definition: u16,
+    pub instance: u16,
+}
+
+impl Zone {
+    pub fn continent(&self) -> Continent {
+        Continent::from(self.definition)
+    }
+
+    pub fn is_instanced(&self) -> bool {
+        self.instance != 0
+    }
+
+    pub fn id(&self) -> ZoneID {
+        ZoneID::from(*self)
+    }
+}
+
+impl From<ZoneID> for Zone {
+    fn from(id: ZoneID) -> Self {
+        Self {
+            definition: (id & 0xFFFF) as u16,
+            instance: (id >> 16) as u16,
+        }
+    }
+}
+
+impl From<Zone> for ZoneID {
+    fn from(zone: Zone) -> Self {
+        (ZoneID::from(zone.instance) << 16) | ZoneID::from(zone.definition)
+    }
+}
+
+impl FromStr for Zone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = ZoneID::from_str(s)?;
+
+        Ok(Self::from(id))
+    }
+}
+
+impl Serialize for Zone {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(&self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for Zone {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ZoneRepr {
+            Id(ZoneID),
+            Str(String),
+        }
+
+        match ZoneRepr::deserialize(deserializer)? {
+            ZoneRepr::Id(id) => Ok(Self::from(id)),
+            ZoneRepr::Str(id) => id.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_instanced() {
+            write!(f, "{} (instance {})", self.continent(), self.instance)
+        } else {
+            write!(f, "{}", self.continent())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Continent, Faction, Loadout, Platform, Region, WorldID, Zone};
+
+    #[test]
+    fn unknown_ids_fall_back() {
+        assert_eq!("1000".parse::<WorldID>().unwrap(), WorldID::Genudine);
+        assert!(
+            matches!("77".parse::<WorldID>().unwrap(), WorldID::Unknown(id) if u16::from(id) == 77)
+        );
+        assert_eq!(u16::from(WorldID::from(77)), 77);
+        assert!(matches!(
+            "99".parse::<Loadout>().unwrap(),
+            Loadout::Unknown(_)
+        ));
+        assert!(matches!(
+            "0".parse::<Faction>().unwrap(),
+            Faction::Unknown(_)
+        ));
+        assert!("-1".parse::<WorldID>().is_err());
+
+        assert_eq!(WorldID::Ceres.platform(), Some(Platform::PS4));
+        assert_eq!(WorldID::Ceres.region(), Some(Region::Europe));
+        assert_eq!(WorldID::from(77).region(), None);
+
+        assert_eq!(Loadout::NCMedic.get_faction(), Faction::NC);
+        assert_eq!(Loadout::from(99).get_faction(), Faction::from(0));
+    }
+
+    #[test]
+    fn known_ids_never_decode_to_unknown() {
+        for id in 0..=u16::MAX {
+            let world = WorldID::from(id);
+            assert_eq!(u16::from(world), id);
+            if let WorldID::Unknown(_) = world {
+                assert_eq!(world.platform(), None);
+            }
         }
+
+        assert_eq!(WorldID::from(17), WorldID::Emerald);
+        assert_eq!(Faction::from(2), Faction::NC);
+    }
+
+    #[test]
+    fn serializes_by_variant_name() {
+        assert_eq!(
+            serde_json::to_string(&WorldID::Emerald).unwrap(),
+            "\"Emerald\""
+        );
+        assert_eq!(
+            serde_json::to_string(&WorldID::from(77)).unwrap(),
+            r#"{"Unknown":77}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Loadout::NCMedic).unwrap(),
+            "\"NCMedic\""
+        );
+        assert_eq!(
+            serde_json::from_str::<WorldID>(r#"{"Unknown":77}"#).unwrap(),
+            WorldID::from(77)
+        );
+        assert_eq!(
+            serde_json::from_str::<WorldID>(r#"{"Unknown":17}"#).unwrap(),
+            WorldID::Emerald
+        );
+        assert_eq!(
+            serde_json::from_str::<Faction>("\"NC\"").unwrap(),
+            Faction::NC
+        );
+        assert_eq!(
+            serde_json::from_str::<Faction>("\"Unknown\"").unwrap(),
+            Faction::from(0)
+        );
+        assert!(serde_json::from_str::<Faction>("\"Purple\"").is_err());
+    }
+
+    #[test]
+    fn decodes_instanced_zones() {
+        let zone = Zone::from(0x0003_0169);
+        assert_eq!(zone.continent(), Continent::Desolation);
+        assert_eq!(zone.instance, 3);
+        assert_eq!(zone.to_string(), "Desolation (instance 3)");
+        assert_eq!(zone.id(), 0x0003_0169);
+
+        let indar = "2".parse::<Zone>().unwrap();
+        assert_eq!(indar.continent(), Continent::Indar);
+        assert!(!indar.is_instanced());
+        assert_eq!(indar.to_string(), "Indar");
+
+        assert_eq!(serde_json::to_string(&zone).unwrap(), "\"196969\"");
+        assert_eq!(serde_json::from_str::<Zone>("\"196969\"").unwrap(), zone);
+        assert_eq!(serde_json::from_str::<Zone>("196969").unwrap(), zone);
+        assert!(serde_json::from_str::<Zone>("\"Indar\"").is_err());
+    }
+
+    #[test]
+    fn decodes_nexus_and_tutorial_zones() {
+        assert_eq!(Zone::from(10).continent(), Continent::Nexus);
+        assert_eq!(Zone::from(0x0002_000A).to_string(), "Nexus (instance 2)");
+        assert_eq!(Zone::from(364).continent(), Continent::Tutorial);
+        assert_eq!(Zone::from(364).to_string(), "Tutorial");
     }
 }
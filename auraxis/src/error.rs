@@ -0,0 +1,108 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AuraxisError {
+    #[error("Websocket error")]
+    WebSocketError(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("Ser(de) error")]
+    SerdeError(#[from] serde_json::Error),
+    #[error("Http error")]
+    #[cfg(feature = "api")]
+    HttpError(#[from] reqwest::Error),
+    #[error("Realtime client is already connected")]
+    AlreadyConnected,
+    #[error("Realtime client is not connected")]
+    NotConnected,
+    /// Census requires a service ID, see <https://census.daybreakgames.com/#devSignup>.
+    #[error("Missing Census service ID for {url}")]
+    MissingServiceId { url: String },
+    /// Census answered with an error instead of the requested collection.
+    #[error("Census error {code} for {collection} at {url}: {message}")]
+    CensusError {
+        collection: String,
+        url: String,
+        code: String,
+        message: String,
+    },
+    #[error("Census rate limited the request for {collection} at {url}")]
+    RateLimited { collection: String, url: String },
+    /// Census answered with something that is not a collection response.
+    #[error("Invalid Census response for {collection} at {url}: {body_excerpt}")]
+    InvalidResponse {
+        collection: String,
+        url: String,
+        body_excerpt: String,
+    },
+    #[error("Timed out waiting for {operation}")]
+    Timeout { operation: String },
+    #[error("Unknown event name {event_name}")]
+    UnknownEventName { event_name: String },
+    #[error("Invalid subscription: {reason}")]
+    InvalidSubscription { reason: String },
+    /// A push service endpoint not shaped like `EventServerEndpoint_Emerald_17`.
+    #[error("Invalid service endpoint {endpoint}: {reason}")]
+    InvalidServiceEndpoint { endpoint: String, reason: String },
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+/// Replace the service ID in a Census URL, so the URL can be logged and
+/// included in errors.
+pub(crate) fn redact_service_id(url: &str) -> String {
+    let mut redacted = String::with_capacity(url.len());
+    let mut rest = url;
+
+    while let Some(index) = rest.find("s:") {
+        let (before, after) = rest.split_at(index);
+        redacted.push_str(before);
+
+        if before.ends_with(['/', '=']) {
+            redacted.push_str("s:<redacted>");
+            let end = after.find(['/', '&', '?', '#']).unwrap_or(after.len());
+            rest = &after[end..];
+        } else {
+            redacted.push_str("s:");
+            rest = &after[2..];
+        }
+    }
+
+    redacted.push_str(rest);
+    redacted
+}
+
+/// At most `max_chars` characters of a response body, for error messages.
+pub(crate) fn body_excerpt(body: &str, max_chars: usize) -> String {
+    match body.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{body_excerpt, redact_service_id};
+
+    #[test]
+    fn redacts_service_ids() {
+        assert_eq!(
+            redact_service_id("https://census.daybreakgames.com/s:secret/get/ps2:v2/character"),
+            "https://census.daybreakgames.com/s:<redacted>/get/ps2:v2/character"
+        );
+        assert_eq!(
+            redact_service_id(
+                "wss://push.nanite-systems.net/streaming?environment=ps2&service-id=s:secret"
+            ),
+            "wss://push.nanite-systems.net/streaming?environment=ps2&service-id=s:<redacted>"
+        );
+        assert_eq!(
+            redact_service_id("https://census.daybreakgames.com/get/ps2:v2/character"),
+            "https://census.daybreakgames.com/get/ps2:v2/character"
+        );
+    }
+
+    #[test]
+    fn truncates_body_excerpts() {
+        assert_eq!(body_excerpt("short", 10), "short");
+        assert_eq!(body_excerpt("<html>é long body</html>", 7), "<html>é...");
+    }
+}
@@ -5,24 +5,11 @@
 #[cfg(feature = "api")]
 pub mod api;
 mod constants;
+mod error;
 pub mod realtime;
 
 pub use constants::*;
-
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum AuraxisError {
-    #[error("Websocket error")]
-    WebSocketError(#[from] tokio_tungstenite::tungstenite::Error),
-    #[error("Ser(de) error")]
-    SerdeError(#[from] serde_json::Error),
-    #[error("Http error")]
-    #[cfg(feature = "api")]
-    HttpError(#[from] reqwest::Error),
-    #[error(transparent)]
-    Unknown(#[from] anyhow::Error),
-}
+pub use error::AuraxisError;
 
 pub type CharacterID = u64;
 pub type OutfitID = u64;
@@ -32,3 +19,5 @@ pub type ExperienceID = u16;
 pub type VehicleID = u16;
 pub type WeaponID = u32;
 pub type FiremodeID = u32;
+pub type AchievementID = u32;
+pub type SkillID = u32;
@@ -0,0 +1,268 @@
+use crate::realtime::event::Event;
+
+use metrics::counter;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+/// What the client does with new events when the consumer falls behind and the
+/// event channel is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the consumer. Nothing is dropped, but the connection is not
+    /// read (and pings are not answered) while waiting.
+    #[default]
+    Block,
+    /// Drop the incoming event.
+    DropNewest,
+    /// Drop the oldest queued event to make room.
+    DropOldest,
+    /// Drop the oldest queued event of the lowest priority, see
+    /// [`Event::priority`]. The incoming event is dropped if everything
+    /// queued is more important.
+    DropByPriority,
+}
+
+impl OverflowPolicy {
+    fn label(&self) -> &'static str {
+        match self {
+            OverflowPolicy::Block => "block",
+            OverflowPolicy::DropNewest => "drop_newest",
+            OverflowPolicy::DropOldest => "drop_oldest",
+            OverflowPolicy::DropByPriority => "drop_by_priority",
+        }
+    }
+}
+
+/// Consumer closed the event channel.
+#[derive(Debug)]
+pub(crate) struct ConsumerClosed;
+
+/// Where the read loop delivers events.
+#[derive(Debug, Clone)]
+pub(crate) enum EventSink {
+    Channel(Sender<Event>),
+    Queue(Arc<EventQueue>),
+}
+
+impl EventSink {
+    /// Create the sink and the receiver handed to the consumer.
+    ///
+    /// With a dropping policy the events are buffered in an [`EventQueue`]
+    /// that has to be drained into the receiver by [`EventQueue::forward`].
+    pub(crate) fn channel(capacity: usize, policy: OverflowPolicy) -> (Self, Receiver<Event>) {
+        match policy {
+            OverflowPolicy::Block => {
+                let (events_tx, events_rx) = mpsc::channel::<Event>(capacity.max(1));
+                (EventSink::Channel(events_tx), events_rx)
+            }
+            policy => {
+                let (events_tx, events_rx) = mpsc::channel::<Event>(1);
+                let queue = EventQueue::new(events_tx, capacity, policy);
+                (EventSink::Queue(Arc::new(queue)), events_rx)
+            }
+        }
+    }
+
+    pub(crate) async fn send(&self, event: Event) -> Result<(), ConsumerClosed> {
+        match self {
+            EventSink::Channel(events) => events.send(event).await.map_err(|_| ConsumerClosed),
+            EventSink::Queue(queue) => queue.push(event),
+        }
+    }
+
+    /// Deliver `event` without waiting for a full channel.
+    pub(crate) fn try_send(&self, event: Event) -> Result<(), TrySendError<()>> {
+        match self {
+            EventSink::Channel(events) => events.try_send(event).map_err(|err| match err {
+                TrySendError::Full(_) => TrySendError::Full(()),
+                TrySendError::Closed(_) => TrySendError::Closed(()),
+            }),
+            EventSink::Queue(queue) => queue
+                .push(event)
+                .map_err(|ConsumerClosed| TrySendError::Closed(())),
+        }
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        match self {
+            EventSink::Channel(events) => events.is_closed(),
+            EventSink::Queue(queue) => queue.events.is_closed(),
+        }
+    }
+}
+
+/// Events waiting for the consumer, bounded by `capacity` and trimmed
+/// according to `policy`.
+#[derive(Debug)]
+pub(crate) struct EventQueue {
+    events: Sender<Event>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    buffer: Mutex<VecDeque<Event>>,
+    ready: Notify,
+}
+
+impl EventQueue {
+    fn new(events: Sender<Event>, capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            events,
+            capacity: capacity.max(1),
+            policy,
+            buffer: Mutex::new(VecDeque::new()),
+            ready: Notify::new(),
+        }
+    }
+
+    fn push(&self, event: Event) -> Result<(), ConsumerClosed> {
+        if self.events.is_closed() {
+            return Err(ConsumerClosed);
+        }
+
+        let dropped = {
+            let mut buffer = self.buffer.lock().expect("realtime event queue poisoned");
+            enqueue(&mut buffer, self.capacity, self.policy, event)
+        };
+
+        if dropped.is_some() {
+            counter!("realtime_events_dropped", "policy" => self.policy.label()).increment(1);
+        }
+        self.ready.notify_one();
+
+        Ok(())
+    }
+
+    /// Move queued events into the consumer channel until the consumer closes it.
+    pub(crate) async fn forward(&self) {
+        loop {
+            let event = self
+                .buffer
+                .lock()
+                .expect("realtime event queue poisoned")
+                .pop_front();
+
+            let Some(event) = event else {
+                tokio::select! {
+                    _ = self.ready.notified() => continue,
+                    _ = self.events.closed() => return,
+                }
+            };
+
+            if self.events.send(event).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Add `event` to `buffer`, returning the event dropped to stay within
+/// `capacity`, if any.
+fn enqueue(
+    buffer: &mut VecDeque<Event>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    event: Event,
+) -> Option<Event> {
+    if buffer.len() < capacity {
+        buffer.push_back(event);
+        return None;
+    }
+
+    match policy {
+        OverflowPolicy::Block | OverflowPolicy::DropNewest => Some(event),
+        OverflowPolicy::DropOldest => {
+            let dropped = buffer.pop_front();
+            buffer.push_back(event);
+            dropped
+        }
+        OverflowPolicy::DropByPriority => {
+            let lowest = buffer
+                .iter()
+                .enumerate()
+                .min_by_key(|(index, queued)| (queued.priority(), *index))
+                .map(|(index, queued)| (index, queued.priority()));
+
+            match lowest {
+                Some((index, priority)) if priority <= event.priority() => {
+                    let dropped = buffer.remove(index);
+                    buffer.push_back(event);
+                    dropped
+                }
+                _ => Some(event),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OverflowPolicy, enqueue};
+    use crate::realtime::event::{ContinentLock, Event, GainExperience};
+    use crate::{Faction, Loadout, WorldID};
+    use chrono::{TimeZone, Utc};
+    use std::collections::VecDeque;
+
+    fn experience(character_id: u64) -> Event {
+        Event::GainExperience(GainExperience {
+            character_id,
+            experience_id: 1,
+            loadout_id: Loadout::NCMedic,
+            other_id: 0,
+            timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+            world_id: WorldID::Emerald,
+            zone_id: 2,
+            amount: 100,
+            team_id: Faction::NC,
+        })
+    }
+
+    fn continent_lock() -> Event {
+        Event::ContinentLock(ContinentLock {
+            timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+            world_id: WorldID::Emerald,
+            zone_id: 2,
+            triggering_faction: Faction::NC,
+            previous_faction: Faction::TR,
+            vs_population: 0,
+            nc_population: 0,
+            tr_population: 0,
+            metagame_event_id: 0,
+        })
+    }
+
+    #[test]
+    fn drop_newest_and_oldest() {
+        let mut buffer = VecDeque::from([experience(1), experience(2)]);
+
+        let dropped = enqueue(&mut buffer, 2, OverflowPolicy::DropNewest, experience(3));
+        assert_eq!(dropped, Some(experience(3)));
+
+        let dropped = enqueue(&mut buffer, 2, OverflowPolicy::DropOldest, experience(3));
+        assert_eq!(dropped, Some(experience(1)));
+        assert_eq!(buffer, VecDeque::from([experience(2), experience(3)]));
+    }
+
+    #[test]
+    fn drop_by_priority_keeps_important_events() {
+        let mut buffer = VecDeque::from([continent_lock(), experience(1)]);
+
+        let dropped = enqueue(
+            &mut buffer,
+            2,
+            OverflowPolicy::DropByPriority,
+            continent_lock(),
+        );
+        assert_eq!(dropped, Some(experience(1)));
+
+        let dropped = enqueue(
+            &mut buffer,
+            2,
+            OverflowPolicy::DropByPriority,
+            experience(2),
+        );
+        assert_eq!(dropped, Some(experience(2)));
+        assert_eq!(buffer, VecDeque::from([continent_lock(), continent_lock()]));
+    }
+}
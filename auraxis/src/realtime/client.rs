@@ -1,22 +1,40 @@
-use super::Message as CensusMessage;
-use crate::AuraxisError;
-use crate::realtime::{Action, Event, REALTIME_URL, SubscriptionSettings};
+use super::{Message as CensusMessage, RecentCharacters, Subscription};
+use crate::realtime::backpressure::{EventQueue, EventSink, OverflowPolicy};
+use crate::realtime::dedup::Deduplicator;
+use crate::realtime::filter::EventFilter;
+use crate::realtime::health::ServiceHealth;
+use crate::realtime::lifecycle::{ConnectionEvent, ConnectionTracker};
+use crate::realtime::reconnect::ReconnectPolicy;
+use crate::realtime::recording::EventRecorder;
+use crate::realtime::subscription::{
+    CharacterSubscription, EventSubscription, SubscriptionCounts, SubscriptionHandle,
+    WorldSubscription, subscription_diff,
+};
+use crate::realtime::watchdog::{Watchdog, WatchdogConfig};
+use crate::realtime::{Action, Event, REALTIME_URL, Service, SubscriptionSettings};
+use crate::{AuraxisError, CharacterID};
+use std::collections::VecDeque;
 use std::io;
 use std::pin::Pin;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::task::{Context, Poll};
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{Future, Sink, SinkExt, Stream, StreamExt};
-use metrics::{counter, describe_counter};
-use stream_reconnect::{ReconnectStream, UnderlyingStream};
+use metrics::{counter, describe_counter, describe_histogram, histogram};
+use stream_reconnect::{ReconnectOptions, ReconnectStream, UnderlyingStream};
 use tokio::net::TcpStream;
-use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender};
-use tokio::sync::watch;
+use tokio::sync::mpsc::error::{SendError, TrySendError};
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{broadcast, oneshot, watch};
+use tokio::task::JoinHandle;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::tungstenite::error::Error as WsError;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 use tracing::{debug, error, info, warn};
 
@@ -25,6 +43,24 @@ pub struct RealtimeClientConfig {
     pub environment: String,
     pub service_id: String,
     pub realtime_url: Option<String>,
+    pub reconnect: ReconnectPolicy,
+    /// Periodically echo a payload through Census and record the round trip
+    /// in the `realtime_echo_round_trip_seconds` histogram.
+    pub latency_probe_interval: Option<Duration>,
+    pub watchdog: WatchdogConfig,
+    /// Drop events identical to one already delivered within this window.
+    /// Suppressed events are counted in `realtime_events_duplicates_suppressed`.
+    pub dedup_window: Option<Duration>,
+    /// Number of events buffered for the consumer of [`RealtimeClient::connect`].
+    pub event_channel_capacity: usize,
+    /// What to do with events once `event_channel_capacity` is reached.
+    /// Dropped events are counted in `realtime_events_dropped`.
+    pub overflow_policy: OverflowPolicy,
+    /// Record every raw event payload received, see [`EventRecorder`].
+    pub recorder: Option<EventRecorder>,
+    /// Record `realtime_events_received`, `realtime_events_parse_failures`
+    /// and `realtime_event_lag_seconds` labelled by event name and world.
+    pub event_metrics: bool,
 }
 
 impl Default for RealtimeClientConfig {
@@ -33,6 +69,14 @@ impl Default for RealtimeClientConfig {
             environment: String::from("ps2"),
             service_id: String::new(),
             realtime_url: None,
+            reconnect: ReconnectPolicy::default(),
+            latency_probe_interval: None,
+            watchdog: WatchdogConfig::default(),
+            dedup_window: None,
+            event_channel_capacity: 1000,
+            overflow_policy: OverflowPolicy::default(),
+            recorder: None,
+            event_metrics: false,
         }
     }
 }
@@ -41,17 +85,60 @@ impl Default for RealtimeClientConfig {
 pub struct RealtimeClient {
     config: Arc<RealtimeClientConfig>,
     state: Arc<RwLock<RealtimeClientState>>,
+    health: Arc<watch::Sender<ServiceHealth>>,
+    connection: Arc<ConnectionTracker>,
+    watchdog: Arc<Watchdog>,
+    dedup: Option<Arc<Mutex<Deduplicator>>>,
+    /// Echo payloads waiting to come back, apart from `state` as every text
+    /// frame is checked against them.
+    pending_echoes: Arc<Mutex<Vec<PendingEcho>>>,
 }
 
-#[derive(Debug, Clone)]
+/// An echoed payload and who is waiting for it.
+type PendingEcho = (serde_json::Value, oneshot::Sender<()>);
+
+/// How long to wait for the close frame to be written when shutting down.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often a subscription is resent in a row when Census keeps
+/// acknowledging something else.
+const MAX_SUBSCRIPTION_RESENDS: usize = 3;
+
+#[derive(Debug)]
 struct RealtimeClientState {
     subscription_config: SubscriptionSettings,
+    subscription_counts: SubscriptionCounts,
     ws_send: Option<UnboundedSender<Message>>,
+    shutdown: Option<watch::Sender<bool>>,
+    tasks: Vec<JoinHandle<Result<(), AuraxisError>>>,
+    pending_recent_character_ids: VecDeque<oneshot::Sender<Vec<CharacterID>>>,
+    pending_recent_character_ids_count: VecDeque<oneshot::Sender<u64>>,
+    listeners: Arc<[Listener]>,
+    acknowledged_subscription: Option<Subscription>,
+    subscription_resends: usize,
+    unacknowledged_subscription_actions: usize,
+    census_greeted: bool,
 }
 
-struct WebSocket(WebSocketStream<MaybeTlsStream<TcpStream>>);
+#[derive(Debug, Clone)]
+struct Listener {
+    filter: Arc<EventFilter>,
+    events: EventSink,
+}
+
+struct WebSocket {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    watchdog: Arc<Watchdog>,
+}
+
+#[derive(Debug, Clone)]
+struct ConnectTarget {
+    url: String,
+    timeout: Duration,
+    watchdog: Arc<Watchdog>,
+}
 
-type ReconnectWs = ReconnectStream<WebSocket, String, Result<Message, WsError>, WsError>;
+type ReconnectWs = ReconnectStream<WebSocket, ConnectTarget, Result<Message, WsError>, WsError>;
 
 impl RealtimeClient {
     #[must_use]
@@ -68,6 +155,10 @@ impl RealtimeClient {
             "realtime_messages_received_total_errored",
             "Total number of messages received from Census stream that errored"
         );
+        describe_counter!(
+            "realtime_messages_received_unknown",
+            "Total number of messages or events received from Census stream that could not be parsed into a known type"
+        );
         describe_counter!(
             "realtime_total_closed_connections",
             "Total number of closed connections to Census stream"
@@ -92,16 +183,115 @@ impl RealtimeClient {
             "realtime_total_resubscriptions",
             "Total number of resubscriptions to Census stream"
         );
+        describe_counter!(
+            "realtime_watchdog_reconnects",
+            "Total number of reconnects forced because the Census stream went stale"
+        );
+        describe_counter!(
+            "realtime_events_duplicates_suppressed",
+            "Total number of duplicate events from Census stream that were dropped"
+        );
+        describe_counter!(
+            "realtime_subscription_mismatches",
+            "Total number of subscription acknowledgements that did not match the client subscription"
+        );
+        describe_counter!(
+            "realtime_events_dropped",
+            "Total number of events dropped because the consumer fell behind"
+        );
+        describe_counter!(
+            "realtime_recorder_messages_dropped",
+            "Total number of event payloads not recorded because the recording writer fell behind"
+        );
+        describe_counter!(
+            "realtime_listener_events_dropped",
+            "Total number of events dropped because a listener with a blocking policy fell behind"
+        );
+        describe_histogram!(
+            "realtime_echo_round_trip_seconds",
+            metrics::Unit::Seconds,
+            "Round trip time of echo messages sent through Census stream"
+        );
+        describe_counter!(
+            "realtime_echo_timeouts",
+            "Total number of latency probe echoes that were not returned in time"
+        );
+        describe_counter!(
+            "realtime_events_received",
+            "Total number of events received from Census stream by event name and world"
+        );
+        describe_counter!(
+            "realtime_events_parse_failures",
+            "Total number of events of a known event name from Census stream that could not be parsed"
+        );
+        describe_counter!(
+            "realtime_events_unknown",
+            "Total number of events from Census stream with an event name this crate does not know"
+        );
+        describe_counter!(
+            "realtime_events_ahead_of_clock",
+            "Total number of events from Census stream timestamped after they were received, by world"
+        );
+        describe_histogram!(
+            "realtime_event_lag_seconds",
+            metrics::Unit::Seconds,
+            "Time between the timestamp of an event and its arrival from Census stream by world"
+        );
+
+        let dedup = config
+            .dedup_window
+            .map(|window| Arc::new(Mutex::new(Deduplicator::new(window))));
 
         Self {
             config: Arc::new(config),
             state: Arc::new(RwLock::new(RealtimeClientState {
                 subscription_config: SubscriptionSettings::empty(),
+                subscription_counts: SubscriptionCounts::default(),
                 ws_send: None,
+                shutdown: None,
+                tasks: Vec::new(),
+                pending_recent_character_ids: VecDeque::new(),
+                pending_recent_character_ids_count: VecDeque::new(),
+                listeners: Arc::new([]),
+                acknowledged_subscription: None,
+                subscription_resends: 0,
+                unacknowledged_subscription_actions: 0,
+                census_greeted: false,
             })),
+            health: Arc::new(watch::channel(ServiceHealth::default()).0),
+            connection: Arc::new(ConnectionTracker::new()),
+            watchdog: Arc::new(Watchdog::new()),
+            dedup,
+            pending_echoes: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// Listen for changes in the state of the connection to Census.
+    ///
+    /// Only events sent after this call are received. Use the `Disconnected` and
+    /// `Connected` pair to find the window in which events were missed.
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.connection.subscribe()
+    }
+
+    /// Watch the health of the Census push service endpoints.
+    ///
+    /// The value is updated on every heartbeat and whenever Census reports a
+    /// change in service state, so it can be used to tell a degraded world
+    /// apart from a quiet one.
+    pub fn service_health(&self) -> watch::Receiver<ServiceHealth> {
+        self.health.subscribe()
+    }
+
+    /// The subscription last acknowledged by Census on the current connection.
+    pub fn acknowledged_subscription(&self) -> Option<Subscription> {
+        self.state
+            .read()
+            .expect("realtime client state poisoned")
+            .acknowledged_subscription
+            .clone()
+    }
+
     /// Send a message to the websocket connection.
     ///
     /// This function will be spawned as a task and will run concurrently to the
@@ -117,79 +307,546 @@ impl RealtimeClient {
     ///
     /// # Errors
     ///
-    /// This function will return an error if the websocket connection cannot be created.
+    /// This function will return an error if the client is already connected,
+    /// no service ID is configured or the websocket connection cannot be created.
     pub async fn connect(&mut self) -> Result<Receiver<Event>, AuraxisError> {
         if self.current_ws_sender().is_some() {
-            return Err(anyhow::anyhow!("RealtimeClient is already connected").into());
+            return Err(AuraxisError::AlreadyConnected);
+        }
+
+        let realtime_url = self.config.realtime_url.as_deref().unwrap_or(REALTIME_URL);
+        if self.config.service_id.is_empty() {
+            return Err(AuraxisError::MissingServiceId {
+                url: realtime_url.to_string(),
+            });
         }
 
         let census_addr = format!(
             "{}?environment={}&service-id=s:{}",
-            self.config.realtime_url.as_deref().unwrap_or(REALTIME_URL),
-            self.config.environment,
-            self.config.service_id
+            realtime_url, self.config.environment, self.config.service_id
         );
 
-        let websocket = ReconnectWs::connect(census_addr).await?;
+        let target = ConnectTarget {
+            url: census_addr,
+            timeout: self.config.reconnect.connect_timeout,
+            watchdog: self.watchdog.clone(),
+        };
+        self.connection.reset();
+        let websocket = ReconnectWs::connect_with_options(target, self.reconnect_options()).await?;
 
         let (ws_send, ws_recv) = websocket.split();
         let (ws_send_tx, ws_send_rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
-        let (event_stream_tx, event_stream_rx) = tokio::sync::mpsc::channel::<Event>(1000);
+        let (events, event_stream_rx) = EventSink::channel(
+            self.config.event_channel_capacity,
+            self.config.overflow_policy,
+        );
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
-        self.set_ws_sender(Some(ws_send_tx.clone()));
-
-        tokio::spawn(Self::send_ws(ws_send, ws_send_rx, shutdown_rx.clone()));
-        tokio::spawn(Self::ping_ws(ws_send_tx.clone(), shutdown_rx.clone()));
-        tokio::spawn(Self::resubscribe(
-            self.clone(),
-            ws_send_tx.clone(),
-            shutdown_rx.clone(),
-        ));
-        tokio::spawn(Self::read_ws(
-            self.clone(),
-            ws_send_tx,
-            ws_recv,
-            event_stream_tx,
-            shutdown_tx,
-            shutdown_rx,
-        ));
+        let mut tasks = vec![
+            tokio::spawn(Self::send_ws(ws_send, ws_send_rx, shutdown_rx.clone())),
+            tokio::spawn(Self::ping_ws(ws_send_tx.clone(), shutdown_rx.clone())),
+            tokio::spawn(Self::resubscribe(
+                self.clone(),
+                ws_send_tx.clone(),
+                shutdown_rx.clone(),
+            )),
+            tokio::spawn(Self::read_ws(
+                self.clone(),
+                ws_send_tx.clone(),
+                ws_recv,
+                events.clone(),
+                shutdown_tx.clone(),
+                shutdown_rx.clone(),
+            )),
+        ];
+
+        if let EventSink::Queue(queue) = events {
+            tasks.push(tokio::spawn(Self::forward_events(
+                queue,
+                shutdown_rx.clone(),
+            )));
+        }
+
+        if self.config.watchdog.pong_timeout.is_some()
+            || self.config.watchdog.silence_timeout.is_some()
+        {
+            tasks.push(tokio::spawn(Self::watch_connection(
+                self.clone(),
+                shutdown_rx.clone(),
+            )));
+        }
+
+        if let Some(interval) = self.config.latency_probe_interval {
+            tasks.push(tokio::spawn(Self::probe_latency(
+                self.clone(),
+                interval,
+                shutdown_rx.clone(),
+            )));
+        }
+
+        {
+            let mut state = self.state.write().expect("realtime client state poisoned");
+            state.ws_send = Some(ws_send_tx);
+            state.unacknowledged_subscription_actions = 0;
+            state.census_greeted = false;
+            state.shutdown = Some(shutdown_tx);
+            state.tasks = tasks;
+        }
 
         Ok(event_stream_rx)
     }
 
-    pub fn subscribe(&mut self, subscription: SubscriptionSettings) {
-        let ws_send = {
+    /// Close the connection to Census.
+    ///
+    /// Sends a close frame, stops all background tasks and resolves once they
+    /// have finished. The event receiver returned by [`Self::connect`] is closed
+    /// afterwards. Subscriptions are kept, so calling [`Self::connect`] again
+    /// resumes the same stream.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client is not connected.
+    pub async fn disconnect(&mut self) -> Result<(), AuraxisError> {
+        let (shutdown, tasks) = {
             let mut state = self.state.write().expect("realtime client state poisoned");
-            state.subscription_config.merge(subscription);
-            state.ws_send.clone()
+            state.ws_send = None;
+            state.pending_recent_character_ids.clear();
+            state.pending_recent_character_ids_count.clear();
+            (state.shutdown.take(), std::mem::take(&mut state.tasks))
+        };
+        self.pending_echoes
+            .lock()
+            .expect("realtime client echoes poisoned")
+            .clear();
+
+        let Some(shutdown) = shutdown else {
+            return Err(AuraxisError::NotConnected);
+        };
+
+        signal_shutdown(&shutdown);
+
+        for task in tasks {
+            match task.await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => debug!("Realtime task stopped with error: {err}"),
+                Err(err) => warn!("Realtime task failed to stop cleanly: {err}"),
+            }
+        }
+
+        info!("Disconnected from Census");
+        self.connection.closed(
+            CloseCode::Normal.into(),
+            String::from("Client disconnected"),
+        );
+        self.connection.disconnected();
+
+        Ok(())
+    }
+
+    /// Ask Census for the characters that have recently been active.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client is not connected or
+    /// Census does not reply within `timeout`.
+    pub async fn recent_character_ids(
+        &self,
+        timeout: Duration,
+    ) -> Result<Vec<CharacterID>, AuraxisError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.state
+            .write()
+            .expect("realtime client state poisoned")
+            .pending_recent_character_ids
+            .push_back(reply_tx);
+
+        self.send_action(&Action::RecentCharacterIds {
+            service: Service::Event,
+        })?;
+
+        Self::await_reply(reply_rx, timeout, "recentCharacterIds").await
+    }
+
+    /// Ask Census how many characters have recently been active.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client is not connected or
+    /// Census does not reply within `timeout`.
+    pub async fn recent_character_ids_count(&self, timeout: Duration) -> Result<u64, AuraxisError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.state
+            .write()
+            .expect("realtime client state poisoned")
+            .pending_recent_character_ids_count
+            .push_back(reply_tx);
+
+        self.send_action(&Action::RecentCharacterIdsCount {
+            service: Service::Event,
+        })?;
+
+        Self::await_reply(reply_rx, timeout, "recentCharacterIdsCount").await
+    }
+
+    /// Send `payload` to Census and wait for it to be echoed back.
+    ///
+    /// Returns the round trip time, which tells a slow stream apart from a
+    /// quiet one. The payload must not look like a Census message, such as an
+    /// object with a `type` or `subscription` field, or it is handled as one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client is not connected or
+    /// the payload is not echoed back within `timeout`.
+    pub async fn echo(
+        &self,
+        payload: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<Duration, AuraxisError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.pending_echoes
+            .lock()
+            .expect("realtime client echoes poisoned")
+            .push((payload.clone(), reply_tx));
+
+        let sent_at = Instant::now();
+        self.send_action(&Action::Echo {
+            payload,
+            service: Service::Event,
+        })?;
+
+        Self::await_reply(reply_rx, timeout, "echo").await?;
+
+        Ok(sent_at.elapsed())
+    }
+
+    async fn probe_latency(
+        self,
+        interval: Duration,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Result<(), AuraxisError> {
+        let mut probe_id = 0u64;
+
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => return Ok(()),
+                _ = tokio::time::sleep(interval) => {}
+            }
+
+            probe_id += 1;
+            let payload = serde_json::json!({ "auraxis_latency_probe": probe_id });
+
+            let result = tokio::select! {
+                _ = shutdown.changed() => return Ok(()),
+                result = self.echo(payload, interval) => result,
+            };
+
+            match result {
+                Ok(round_trip) => {
+                    histogram!("realtime_echo_round_trip_seconds").record(round_trip.as_secs_f64());
+                }
+                Err(err) => {
+                    counter!("realtime_echo_timeouts").increment(1);
+                    debug!("Latency probe failed: {err}");
+                }
+            }
+        }
+    }
+
+    /// Resolve the pending echo matching `value`, if any. Census echoes the
+    /// payload back verbatim, so echoes can only be told apart by content.
+    fn resolve_echo(&self, value: &serde_json::Value) -> bool {
+        let mut pending_echoes = self
+            .pending_echoes
+            .lock()
+            .expect("realtime client echoes poisoned");
+        pending_echoes.retain(|(_, pending)| !pending.is_closed());
+
+        let Some(position) = pending_echoes
+            .iter()
+            .position(|(payload, _)| payload == value)
+        else {
+            return false;
+        };
+
+        let (_, pending) = pending_echoes.remove(position);
+        let _ = pending.send(());
+
+        true
+    }
+
+    async fn await_reply<T>(
+        reply: oneshot::Receiver<T>,
+        timeout: Duration,
+        action: &str,
+    ) -> Result<T, AuraxisError> {
+        match tokio::time::timeout(timeout, reply).await {
+            Ok(Ok(reply)) => Ok(reply),
+            Ok(Err(_)) => Err(AuraxisError::NotConnected),
+            Err(_) => Err(AuraxisError::Timeout {
+                operation: format!("{action} reply"),
+            }),
+        }
+    }
+
+    fn send_action(&self, action: &Action) -> Result<(), AuraxisError> {
+        let Some(ws_send) = self.current_ws_sender() else {
+            return Err(AuraxisError::NotConnected);
+        };
+
+        let message = Message::Text(serde_json::to_string(action)?.into());
+        if ws_send.send(message).is_err() {
+            self.set_ws_sender(None);
+            return Err(AuraxisError::NotConnected);
+        }
+
+        Ok(())
+    }
+
+    /// Hand a reply to the oldest caller still waiting for it. Callers that
+    /// already timed out are skipped.
+    fn resolve_recent_characters(&self, reply: RecentCharacters) {
+        let mut state = self.state.write().expect("realtime client state poisoned");
+
+        match reply {
+            RecentCharacters::Ids { character_id_list } => {
+                while let Some(pending) = state.pending_recent_character_ids.pop_front() {
+                    if !pending.is_closed() {
+                        let _ = pending.send(character_id_list);
+                        return;
+                    }
+                }
+            }
+            RecentCharacters::Count { count } => {
+                while let Some(pending) = state.pending_recent_character_ids_count.pop_front() {
+                    if !pending.is_closed() {
+                        let _ = pending.send(count);
+                        return;
+                    }
+                }
+            }
+        }
+
+        debug!("Dropping recent character reply nobody is waiting for");
+    }
+
+    /// Receive the events matching `filter` on a receiver of its own.
+    ///
+    /// Can be called any number of times, before or after [`Self::connect`],
+    /// and every listener shares the same connection. The subscription is
+    /// extended with what the filter needs, see [`EventFilter::subscription`],
+    /// and released again once the receiver is dropped.
+    ///
+    /// A filter on worlds without characters subscribes to the events of
+    /// every character unless it only selects world events, because Census
+    /// can only narrow character events to worlds for the whole connection.
+    /// The listener still only receives the events of its worlds.
+    ///
+    /// Listeners use the configured `event_channel_capacity` and
+    /// `overflow_policy`. With [`OverflowPolicy::Block`] a full listener does
+    /// not hold up the other consumers, its events are dropped instead.
+    ///
+    /// # Panics
+    ///
+    /// This must be called from within a Tokio runtime.
+    pub fn listen(&mut self, filter: EventFilter) -> Receiver<Event> {
+        let (events, events_rx) = EventSink::channel(
+            self.config.event_channel_capacity,
+            self.config.overflow_policy,
+        );
+
+        let subscription = filter.subscription();
+        {
+            let mut state = self.state.write().expect("realtime client state poisoned");
+            let listener = Listener {
+                filter: Arc::new(filter),
+                events: events.clone(),
+            };
+            state.listeners = state.listeners.iter().cloned().chain([listener]).collect();
+        }
+        let subscription = self.subscribe(subscription);
+
+        tokio::spawn(async move {
+            match events {
+                EventSink::Queue(queue) => queue.forward().await,
+                EventSink::Channel(events) => events.closed().await,
+            }
+            subscription.cancel();
+        });
+
+        events_rx
+    }
+
+    /// Deliver `event` to every listener whose filter matches it.
+    ///
+    /// Returns whether any listener is still open.
+    fn dispatch(&self, event: &Event) -> bool {
+        let listeners = self
+            .state
+            .read()
+            .expect("realtime client state poisoned")
+            .listeners
+            .clone();
+        if listeners.is_empty() {
+            return false;
+        }
+
+        let mut closed = false;
+        for listener in listeners.iter() {
+            if !listener.filter.matches(event) {
+                closed |= listener.events.is_closed();
+                continue;
+            }
+
+            match listener.events.try_send(event.clone()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    counter!("realtime_listener_events_dropped").increment(1);
+                }
+                Err(TrySendError::Closed(_)) => closed = true,
+            }
+        }
+
+        if !closed {
+            return true;
+        }
+
+        let mut state = self.state.write().expect("realtime client state poisoned");
+        state.listeners = state
+            .listeners
+            .iter()
+            .filter(|listener| !listener.events.is_closed())
+            .cloned()
+            .collect();
+        !state.listeners.is_empty()
+    }
+
+    /// Add `subscription` to the subscription of the client.
+    ///
+    /// The returned handle keeps it subscribed. Every event, character and
+    /// world is reference counted across handles, so dropping the handle only
+    /// clears the entries that no other handle needs. Use
+    /// [`SubscriptionHandle::detach`] to keep the subscription indefinitely.
+    pub fn subscribe(&mut self, subscription: SubscriptionSettings) -> SubscriptionHandle {
+        let (ws_send, generation) = {
+            let mut state = self.state.write().expect("realtime client state poisoned");
+            state.subscription_counts.add(&subscription);
+            state.subscription_config.merge(subscription.clone());
+            (
+                state.ws_send.clone(),
+                state.subscription_counts.generation(),
+            )
         };
+        let handle = SubscriptionHandle::new(self.clone(), subscription, generation);
 
         let subscribe_message = match self.subscribe_message() {
             Ok(Some(message)) => message,
-            Ok(None) => return,
+            Ok(None) => return handle,
             Err(err) => {
                 error!("Failed to serialize subscription update: {err}");
-                return;
+                return handle;
             }
         };
 
         if let Some(ws_send) = ws_send
-            && let Err(err) = ws_send.send(subscribe_message)
+            && let Err(err) = self.send_subscription_action(&ws_send, subscribe_message)
         {
             warn!("Failed to enqueue live subscription update: {err}");
             self.set_ws_sender(None);
         }
+
+        handle
+    }
+
+    /// Replace the whole subscription with `target`.
+    ///
+    /// Only the entries that change are subscribed and cleared, additions
+    /// first, so events matching both the old and the new subscription keep
+    /// arriving throughout. Handles returned by [`Self::subscribe`] before
+    /// this call no longer clear anything when dropped.
+    ///
+    /// Narrowing a subscription from all events, characters or worlds to
+    /// specific ones can only be done by clearing "all" and subscribing again,
+    /// which may miss events sent in between.
+    pub fn set_subscription(&mut self, target: SubscriptionSettings) {
+        let (ws_send, subscribe, clear) = {
+            let mut state = self.state.write().expect("realtime client state poisoned");
+            let (subscribe, clear) = subscription_diff(&state.subscription_config, &target);
+            state.subscription_counts.reset(&target);
+            state.subscription_config = target.clone();
+            (state.ws_send.clone(), subscribe, clear)
+        };
+
+        let Some(ws_send) = ws_send else {
+            return;
+        };
+
+        if !subscribe.is_empty() {
+            match serde_json::to_string(&Action::Subscribe(subscribe)) {
+                Ok(message) => {
+                    if let Err(err) =
+                        self.send_subscription_action(&ws_send, Message::Text(message.into()))
+                    {
+                        warn!("Failed to enqueue subscription update: {err}");
+                        self.set_ws_sender(None);
+                        return;
+                    }
+                }
+                Err(err) => {
+                    error!("Failed to serialize subscription update: {err}");
+                    return;
+                }
+            }
+        }
+
+        if !clear.is_empty() {
+            self.send_clear(Some(ws_send), &clear, target);
+        }
     }
 
+    /// Remove `subscription`, regardless of the handles still holding it.
     pub fn clear_subscribe(&mut self, subscription: SubscriptionSettings) {
         let (ws_send, current_subscription) = {
             let mut state = self.state.write().expect("realtime client state poisoned");
+            state.subscription_counts.forget(&subscription);
             state.subscription_config.clear(&subscription);
             (state.ws_send.clone(), state.subscription_config.clone())
         };
 
-        let clear_message = match Self::clear_subscribe_message(&subscription) {
+        self.send_clear(ws_send, &subscription, current_subscription);
+    }
+
+    /// Release a subscription held by a dropped [`SubscriptionHandle`].
+    pub(crate) fn release(&self, subscription: &SubscriptionSettings, generation: u64) {
+        let (ws_send, released, current_subscription) = {
+            let mut state = self.state.write().expect("realtime client state poisoned");
+            let released = state.subscription_counts.release(subscription, generation);
+            if released.is_empty() {
+                return;
+            }
+
+            let current_subscription = SubscriptionSettings {
+                logical_and_characters_with_worlds: state
+                    .subscription_config
+                    .logical_and_characters_with_worlds,
+                ..state.subscription_counts.subscription()
+            };
+            state.subscription_config = current_subscription.clone();
+            (state.ws_send.clone(), released, current_subscription)
+        };
+
+        self.send_clear(ws_send, &released, current_subscription);
+    }
+
+    /// Send a clear for `subscription`, followed by `current_subscription`
+    /// when the clear may have removed more than requested.
+    fn send_clear(
+        &self,
+        ws_send: Option<UnboundedSender<Message>>,
+        subscription: &SubscriptionSettings,
+        current_subscription: SubscriptionSettings,
+    ) {
+        let clear_message = match Self::clear_subscribe_message(subscription) {
             Ok(message) => message,
             Err(err) => {
                 error!("Failed to serialize clear subscription update: {err}");
@@ -198,20 +855,24 @@ impl RealtimeClient {
         };
 
         if let Some(ws_send) = ws_send {
-            if let Err(err) = ws_send.send(clear_message) {
+            if let Err(err) = self.send_subscription_action(&ws_send, clear_message) {
                 warn!("Failed to enqueue clear subscription update: {err}");
                 self.set_ws_sender(None);
                 return;
             }
 
-            if subscription.logical_and_characters_with_worlds.is_some()
+            // Clearing "all" also removes the individual entries still needed.
+            let cleared_all = matches!(subscription.event_names, Some(EventSubscription::All))
+                || matches!(subscription.characters, Some(CharacterSubscription::All))
+                || matches!(subscription.worlds, Some(WorldSubscription::All));
+            if (subscription.logical_and_characters_with_worlds.is_some() || cleared_all)
                 && !current_subscription.is_empty()
             {
                 match serde_json::to_string(&Action::Subscribe(current_subscription))
                     .map(|message| Message::Text(message.into()))
                 {
                     Ok(message) => {
-                        if let Err(err) = ws_send.send(message) {
+                        if let Err(err) = self.send_subscription_action(&ws_send, message) {
                             warn!("Failed to enqueue resubscribe after logical-and update: {err}");
                             self.set_ws_sender(None);
                         }
@@ -228,13 +889,16 @@ impl RealtimeClient {
         let ws_send = {
             let mut state = self.state.write().expect("realtime client state poisoned");
             state.subscription_config = SubscriptionSettings::empty();
+            state
+                .subscription_counts
+                .reset(&SubscriptionSettings::empty());
             state.ws_send.clone()
         };
 
         if let Some(ws_send) = ws_send {
             match Self::clear_all_subscribe_message() {
                 Ok(message) => {
-                    if let Err(err) = ws_send.send(message) {
+                    if let Err(err) = self.send_subscription_action(&ws_send, message) {
                         warn!("Failed to enqueue clear-all subscription update: {err}");
                         self.set_ws_sender(None);
                     }
@@ -251,28 +915,24 @@ impl RealtimeClient {
         ws_send: UnboundedSender<Message>,
         mut shutdown: watch::Receiver<bool>,
     ) -> Result<(), AuraxisError> {
+        // Subscribing on connect is handled by `handle_ws_msg`.
         loop {
+            tokio::select! {
+                _ = shutdown.changed() => return Ok(()),
+                _ = tokio::time::sleep(Duration::from_secs(60 * 30)) => {}
+            }
+
             if *shutdown.borrow() {
                 return Ok(());
             }
 
             let Some(message) = self.subscribe_message()? else {
-                tokio::select! {
-                    _ = shutdown.changed() => return Ok(()),
-                    _ = tokio::time::sleep(Duration::from_secs(60 * 30)) => {}
-                }
                 continue;
             };
 
-            let res = ws_send.send(message);
-
-            match res {
+            match self.send_subscription_action(&ws_send, message) {
                 Ok(_) => {
                     counter!("realtime_total_resubscriptions").increment(1);
-                    tokio::select! {
-                        _ = shutdown.changed() => return Ok(()),
-                        _ = tokio::time::sleep(Duration::from_secs(60 * 30)) => {}
-                    }
                 }
                 Err(err) => {
                     warn!("Subscription loop shutting down: {}", err);
@@ -282,6 +942,32 @@ impl RealtimeClient {
         }
     }
 
+    /// Force a reconnect when the connection stops answering pings or goes
+    /// silent, see [`WatchdogConfig`].
+    async fn watch_connection(
+        self,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Result<(), AuraxisError> {
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => return Ok(()),
+                _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+            }
+
+            if self.connection.is_disconnected() {
+                continue;
+            }
+
+            let subscription = self.current_subscription();
+            if let Some(staleness) = self.watchdog.check(&self.config.watchdog, &subscription) {
+                warn!("{}, forcing reconnect", staleness.reason());
+                counter!("realtime_watchdog_reconnects").increment(1);
+                self.connection.stale(staleness.reason().to_string());
+                self.watchdog.trip();
+            }
+        }
+    }
+
     async fn ping_ws(
         ping_send: UnboundedSender<Message>,
         mut shutdown: watch::Receiver<bool>,
@@ -312,7 +998,10 @@ impl RealtimeClient {
     ) -> Result<(), AuraxisError> {
         loop {
             let message = tokio::select! {
-                _ = shutdown.changed() => break,
+                _ = shutdown.changed() => {
+                    Self::close_ws(&mut ws_send).await;
+                    break;
+                }
                 message = ws_send_rx.recv() => message,
             };
 
@@ -331,11 +1020,36 @@ impl RealtimeClient {
         Ok(())
     }
 
+    async fn close_ws(ws_send: &mut SplitSink<ReconnectWs, Message>) {
+        let close = Message::Close(Some(CloseFrame {
+            code: CloseCode::Normal,
+            reason: "Client disconnected".into(),
+        }));
+
+        match tokio::time::timeout(CLOSE_TIMEOUT, ws_send.send(close)).await {
+            Ok(Ok(())) => counter!("realtime_messages_total_sent").increment(1),
+            Ok(Err(err)) => debug!("Failed to send close frame: {err}"),
+            Err(_) => debug!("Timed out sending close frame"),
+        }
+    }
+
+    async fn forward_events(
+        queue: Arc<EventQueue>,
+        mut shutdown: watch::Receiver<bool>,
+    ) -> Result<(), AuraxisError> {
+        tokio::select! {
+            _ = shutdown.changed() => {}
+            _ = queue.forward() => debug!("Event queue consumer closed"),
+        }
+
+        Ok(())
+    }
+
     async fn read_ws(
         self,
         ws_send: UnboundedSender<Message>,
         mut ws_recv: SplitStream<ReconnectWs>,
-        event_stream_tx: Sender<Event>,
+        events: EventSink,
         shutdown_tx: watch::Sender<bool>,
         mut shutdown: watch::Receiver<bool>,
     ) -> Result<(), AuraxisError> {
@@ -353,15 +1067,19 @@ impl RealtimeClient {
             match message {
                 Ok(msg) => {
                     // debug!("Received: {:?}", msg.to_string());
-                    if let Err(err) = Self::handle_ws_msg(
-                        self.clone(),
-                        ws_send.clone(),
-                        event_stream_tx.clone(),
-                        shutdown_tx.clone(),
-                        msg,
-                    )
-                    .await
-                    {
+                    // A full event channel must not keep the client from shutting down.
+                    let result = tokio::select! {
+                        _ = shutdown.changed() => break,
+                        result = Self::handle_ws_msg(
+                            self.clone(),
+                            ws_send.clone(),
+                            events.clone(),
+                            shutdown_tx.clone(),
+                            msg,
+                        ) => result,
+                    };
+
+                    if let Err(err) = result {
                         counter!("realtime_messages_received_total_errored").increment(1);
                         error!("{:?}", err);
                     }
@@ -401,13 +1119,24 @@ impl RealtimeClient {
     async fn handle_ws_msg(
         self,
         ws_send: UnboundedSender<Message>,
-        events: Sender<Event>,
+        events: EventSink,
         shutdown: watch::Sender<bool>,
         msg: Message,
     ) -> Result<(), AuraxisError> {
         match msg {
             Message::Text(text) => {
-                let message: CensusMessage = serde_json::from_str(&text)?;
+                let message = serde_json::from_str::<CensusMessage>(&text);
+                if let Ok(CensusMessage::Unknown(value)) = &message
+                    && self.resolve_echo(value)
+                {
+                    return Ok(());
+                }
+
+                if let Some(recorder) = &self.config.recorder {
+                    recorder.record(&text);
+                }
+
+                let message = message?;
 
                 match message {
                     CensusMessage::ConnectionStateChanged { connected } => {
@@ -415,13 +1144,29 @@ impl RealtimeClient {
                             info!("Connected to Census!");
 
                             counter!("realtime_total_connections").increment(1);
+                            {
+                                let mut state =
+                                    self.state.write().expect("realtime client state poisoned");
+                                state.acknowledged_subscription = None;
+                                state.subscription_resends = 0;
+                                // Actions sent before a reconnect are never
+                                // acknowledged, the ones sent before the first
+                                // greeting are.
+                                if state.census_greeted {
+                                    state.unacknowledged_subscription_actions = 0;
+                                }
+                                state.census_greeted = true;
+                            }
+                            self.connection.connected();
 
                             let Some(subscription_message) = self.subscribe_message()? else {
                                 return Ok(());
                             };
                             debug!("Subscribing with {:?}", subscription_message);
 
-                            if let Err(err) = ws_send.send(subscription_message) {
+                            if let Err(err) =
+                                self.send_subscription_action(&ws_send, subscription_message)
+                            {
                                 signal_shutdown(&shutdown);
                                 debug!(
                                     "Subscription send aborted because ws channel closed: {err}"
@@ -429,23 +1174,62 @@ impl RealtimeClient {
                             }
                         }
                     }
-                    CensusMessage::Heartbeat { .. } => {
+                    CensusMessage::Heartbeat { online } => {
                         counter!("realtime_messages_received_heartbeat").increment(1);
+                        self.health.send_replace(online);
+                    }
+                    CensusMessage::ServiceStateChanged { online, detail } => {
+                        if !online {
+                            warn!("Census service endpoint {} went offline", detail);
+                        }
+                        self.health.send_modify(|health| health.set(detail, online));
                     }
-                    CensusMessage::ServiceStateChanged { .. } => {}
                     CensusMessage::ServiceMessage { payload } => {
-                        if events.send(payload).await.is_err() {
+                        self.watchdog.event();
+                        if self.config.event_metrics {
+                            record_event_metrics(&payload, Utc::now());
+                        }
+
+                        if let Event::Unknown { event_name, .. } = &payload {
+                            counter!("realtime_messages_received_unknown").increment(1);
+                            warn!("Received unknown realtime event {:?}", event_name);
+                        }
+
+                        if let Some(dedup) = &self.dedup
+                            && dedup
+                                .lock()
+                                .expect("realtime dedup state poisoned")
+                                .is_duplicate(&payload, Instant::now())
+                        {
+                            counter!("realtime_events_duplicates_suppressed").increment(1);
+                            return Ok(());
+                        }
+
+                        // The receiver returned by connect may be dropped in favour of listeners.
+                        let listening = self.dispatch(&payload);
+                        if events.send(payload).await.is_err() && !listening {
                             debug!("Dropping realtime event because consumer channel is closed");
                             signal_shutdown(&shutdown);
                             return Ok(());
                         }
                     }
-                    CensusMessage::Subscription { subscription } => {
+                    CensusMessage::Subscription { subscription }
+                    | CensusMessage::SubscriptionAck { subscription } => {
                         debug!("Subscribed: {:?}", subscription);
+                        self.verify_subscription(&ws_send, &subscription);
+                        self.connection.subscribed(subscription);
+                    }
+                    CensusMessage::RecentCharacters { result } => {
+                        self.resolve_recent_characters(result);
+                    }
+                    CensusMessage::Unknown(raw) => {
+                        counter!("realtime_messages_received_unknown").increment(1);
+                        warn!("Received unknown realtime message: {}", raw);
                     }
                 }
             }
-            Message::Binary(_) | Message::Pong(_) | Message::Frame(_) => {}
+            Message::Pong(_) => self.watchdog.pong(),
+            Message::Binary(_) | Message::Frame(_) => {}
             Message::Ping(ping) => {
                 if let Err(err) = ws_send.send(Message::Pong(ping)) {
                     signal_shutdown(&shutdown);
@@ -459,6 +1243,8 @@ impl RealtimeClient {
                         "Websocket closed. Code: {}, Reason: {}",
                         close_frame.code, close_frame.reason
                     );
+                    self.connection
+                        .closed(close_frame.code.into(), close_frame.reason.to_string());
                 }
                 warn!("Websocket close frame received; waiting for reconnect");
             }
@@ -467,6 +1253,111 @@ impl RealtimeClient {
         Ok(())
     }
 
+    fn reconnect_options(&self) -> ReconnectOptions {
+        let on_connect = self.connection.clone();
+        let watchdog = self.watchdog.clone();
+        let on_disconnect = self.connection.clone();
+        let on_retry = self.connection.clone();
+        let policy = self.config.reconnect.clone();
+
+        ReconnectOptions::new()
+            .with_exit_if_first_connect_fails(!policy.retry_initial_connect)
+            .with_on_connect_callback(move || {
+                on_connect.established();
+                watchdog.reset();
+            })
+            .with_on_disconnect_callback(move || on_disconnect.disconnected())
+            .with_retries_generator(move || {
+                let reconnecting = on_retry.clone();
+                let exhausted = on_retry.clone();
+
+                policy
+                    .delays()
+                    .inspect(move |_| reconnecting.reconnecting())
+                    .chain(std::iter::from_fn(move || {
+                        exhausted.exhausted();
+                        None
+                    }))
+            })
+    }
+
+    /// Compare an acknowledged subscription with the local one, subscribing
+    /// to the missing entries and clearing the unexpected ones if they differ.
+    fn verify_subscription(&self, ws_send: &UnboundedSender<Message>, ack: &Subscription) {
+        let (expected, differences, corrections, resend) = {
+            let mut state = self.state.write().expect("realtime client state poisoned");
+            state.acknowledged_subscription = Some(ack.clone());
+
+            // Census acknowledges every action, only the last one reflects
+            // the whole subscription.
+            state.unacknowledged_subscription_actions =
+                state.unacknowledged_subscription_actions.saturating_sub(1);
+            if state.unacknowledged_subscription_actions > 0 {
+                return;
+            }
+
+            let differences = state.subscription_config.ack_differences(ack);
+            if differences.is_empty() {
+                state.subscription_resends = 0;
+                return;
+            }
+
+            state.subscription_resends += 1;
+            (
+                state.subscription_config.clone(),
+                differences,
+                state.subscription_config.ack_corrections(ack),
+                state.subscription_resends <= MAX_SUBSCRIPTION_RESENDS,
+            )
+        };
+
+        counter!("realtime_subscription_mismatches").increment(1);
+        warn!(
+            "Census acknowledged a different subscription: {}",
+            differences.join(", ")
+        );
+        self.connection
+            .subscription_mismatch(expected.clone(), ack.clone());
+
+        if !resend {
+            warn!("Not resending subscription, Census keeps acknowledging a different one");
+            return;
+        }
+
+        let (subscribe, clear) = corrections;
+        if !subscribe.is_empty() {
+            match serde_json::to_string(&Action::Subscribe(subscribe))
+                .map(|message| Message::Text(message.into()))
+            {
+                Ok(message) => {
+                    if let Err(err) = self.send_subscription_action(ws_send, message) {
+                        debug!("Subscription resend aborted because ws channel closed: {err}");
+                        return;
+                    }
+                }
+                Err(err) => error!("Failed to serialize subscription resend: {err}"),
+            }
+        }
+
+        if !clear.is_empty() {
+            self.send_clear(Some(ws_send.clone()), &clear, expected);
+        }
+    }
+
+    /// Send a subscribe or clear action, expecting Census to acknowledge it.
+    fn send_subscription_action(
+        &self,
+        ws_send: &UnboundedSender<Message>,
+        message: Message,
+    ) -> Result<(), SendError<Message>> {
+        self.state
+            .write()
+            .expect("realtime client state poisoned")
+            .unacknowledged_subscription_actions += 1;
+
+        ws_send.send(message)
+    }
+
     fn subscribe_message(&self) -> Result<Option<Message>, AuraxisError> {
         let subscription = self.current_subscription();
         if subscription.is_empty() {
@@ -530,6 +1421,43 @@ impl RealtimeClient {
     }
 }
 
+/// Record the labelled event metrics enabled by `event_metrics`.
+fn record_event_metrics(event: &Event, received_at: DateTime<Utc>) {
+    let event_name = event.to_string();
+    let world = event
+        .world_id()
+        .map_or_else(|| String::from("none"), |world| world.to_string());
+
+    counter!(
+        "realtime_events_received",
+        "event_name" => event_name.clone(),
+        "world" => world.clone()
+    )
+    .increment(1);
+
+    if let Event::Unknown { .. } = event {
+        if event.event_name().is_some() {
+            counter!("realtime_events_parse_failures", "event_name" => event_name).increment(1);
+        } else {
+            counter!("realtime_events_unknown", "event_name" => event_name).increment(1);
+        }
+    }
+
+    // Census timestamps have a resolution of one second, so they can only be
+    // ahead of the time received when the local clock is behind.
+    if let Some(timestamp) = event.timestamp() {
+        match (received_at - timestamp).to_std() {
+            Ok(lag) => {
+                histogram!("realtime_event_lag_seconds", "world" => world)
+                    .record(lag.as_secs_f64());
+            }
+            Err(_) => {
+                counter!("realtime_events_ahead_of_clock", "world" => world).increment(1);
+            }
+        }
+    }
+}
+
 fn signal_shutdown(shutdown: &watch::Sender<bool>) {
     let _ = shutdown.send(true);
 }
@@ -538,7 +1466,12 @@ impl Stream for WebSocket {
     type Item = Result<Message, WsError>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.0).poll_next(cx)
+        // Ending the stream makes the reconnect stream treat it as a disconnect.
+        if self.watchdog.poll_stale(cx) {
+            return Poll::Ready(None);
+        }
+
+        Pin::new(&mut self.stream).poll_next(cx)
     }
 }
 
@@ -546,30 +1479,42 @@ impl Sink<Message> for WebSocket {
     type Error = WsError;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Pin::new(&mut self.0).poll_ready(cx)
+        Pin::new(&mut self.stream).poll_ready(cx)
     }
 
     fn start_send(mut self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
-        Pin::new(&mut self.0).start_send(item)
+        Pin::new(&mut self.stream).start_send(item)
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Pin::new(&mut self.0).poll_flush(cx)
+        Pin::new(&mut self.stream).poll_flush(cx)
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        Pin::new(&mut self.0).poll_close(cx)
+        Pin::new(&mut self.stream).poll_close(cx)
     }
 }
 
-impl UnderlyingStream<String, Result<Message, WsError>, WsError> for WebSocket {
+impl UnderlyingStream<ConnectTarget, Result<Message, WsError>, WsError> for WebSocket {
     // Establishes connection.
     // Additionally, this will be used when reconnect tries are attempted.
-    fn establish(addr: String) -> Pin<Box<dyn Future<Output = Result<Self, WsError>> + Send>> {
+    fn establish(
+        target: ConnectTarget,
+    ) -> Pin<Box<dyn Future<Output = Result<Self, WsError>> + Send>> {
         Box::pin(async move {
             // In this case, we are trying to connect to the WebSocket endpoint
-            let (websocket, _) = connect_async(addr).await?;
-            Ok(WebSocket(websocket))
+            let (websocket, _) = tokio::time::timeout(target.timeout, connect_async(target.url))
+                .await
+                .map_err(|_| {
+                    WsError::Io(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "Timed out connecting to Census",
+                    ))
+                })??;
+            Ok(WebSocket {
+                stream: websocket,
+                watchdog: target.watchdog,
+            })
         })
     }
 
@@ -599,3 +1544,88 @@ impl UnderlyingStream<String, Result<Message, WsError>, WsError> for WebSocket {
         WsError::Io(io::Error::other("Exhausted"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RealtimeClient, RealtimeClientConfig, record_event_metrics};
+    use crate::WorldID;
+    use crate::realtime::event::{Event, PlayerLogin};
+    use chrono::{TimeZone, Utc};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use serde_json::json;
+
+    #[test]
+    fn records_event_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let timestamp = Utc.timestamp_opt(1700000000, 0).unwrap();
+        let login = Event::PlayerLogin(PlayerLogin {
+            character_id: 1,
+            timestamp,
+            world_id: WorldID::Emerald,
+        });
+        let unknown = |event_name: &str| Event::Unknown {
+            event_name: event_name.to_string(),
+            raw: json!({}),
+        };
+
+        metrics::with_local_recorder(&recorder, || {
+            record_event_metrics(&login, timestamp + chrono::Duration::seconds(2));
+            record_event_metrics(&login, timestamp - chrono::Duration::seconds(1));
+            record_event_metrics(&unknown("Death"), timestamp);
+            record_event_metrics(&unknown("NewEvent"), timestamp);
+        });
+
+        let metrics = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key().clone();
+                let labels = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                (format!("{}{{{labels}}}", key.name()), value)
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
+        assert_eq!(
+            metrics["realtime_events_received{event_name=PlayerLogin,world=Emerald}"],
+            DebugValue::Counter(2)
+        );
+        assert_eq!(
+            metrics["realtime_events_parse_failures{event_name=Death}"],
+            DebugValue::Counter(1)
+        );
+        assert_eq!(
+            metrics["realtime_events_unknown{event_name=NewEvent}"],
+            DebugValue::Counter(1)
+        );
+        assert_eq!(
+            metrics["realtime_events_ahead_of_clock{world=Emerald}"],
+            DebugValue::Counter(1)
+        );
+        assert_eq!(
+            metrics["realtime_event_lag_seconds{world=Emerald}"],
+            DebugValue::Histogram(vec![2.0.into()])
+        );
+    }
+
+    #[tokio::test]
+    async fn initial_connect_fails_fast_by_default() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let mut client = RealtimeClient::new(RealtimeClientConfig {
+            service_id: String::from("test"),
+            realtime_url: Some(url),
+            ..RealtimeClientConfig::default()
+        });
+
+        let connect = tokio::time::timeout(std::time::Duration::from_secs(5), client.connect());
+        assert!(connect.await.expect("connect should not retry").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::realtime::event::{Event, MetagameEvent};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// Drops events that were already delivered within `window`.
+///
+/// Census is known to send the same event more than once, for example from
+/// redundant endpoints or around resubscribes.
+#[derive(Debug)]
+pub(crate) struct Deduplicator {
+    window: Duration,
+    seen: HashSet<u64>,
+    expiry: VecDeque<(Instant, u64)>,
+}
+
+impl Deduplicator {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: HashSet::new(),
+            expiry: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn is_duplicate(&mut self, event: &Event, now: Instant) -> bool {
+        while let Some((seen_at, key)) = self.expiry.front()
+            && now.duration_since(*seen_at) > self.window
+        {
+            self.seen.remove(key);
+            self.expiry.pop_front();
+        }
+
+        let key = event_key(event);
+        if !self.seen.insert(key) {
+            return true;
+        }
+
+        self.expiry.push_back((now, key));
+        false
+    }
+}
+
+fn event_key(event: &Event) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    std::mem::discriminant(event).hash(&mut hasher);
+
+    match event {
+        Event::PlayerLogin(event) => event.hash(&mut hasher),
+        Event::PlayerLogout(event) => event.hash(&mut hasher),
+        Event::Death(event) => event.hash(&mut hasher),
+        Event::VehicleDestroy(event) => event.hash(&mut hasher),
+        Event::GainExperience(event) => event.hash(&mut hasher),
+        Event::PlayerFacilityCapture(event) => event.hash(&mut hasher),
+        Event::PlayerFacilityDefend(event) => event.hash(&mut hasher),
+        Event::ContinentLock(event) => event.hash(&mut hasher),
+        Event::ContinentUnlock(event) => event.hash(&mut hasher),
+        Event::FacilityControl(event) => event.hash(&mut hasher),
+        Event::MetagameEvent(MetagameEvent {
+            timestamp,
+            world_id,
+            instance_id,
+            experience_bonus,
+            faction_nc,
+            faction_tr,
+            faction_vs,
+            metagame_event_id,
+            metagame_event_state,
+            metagame_event_state_name,
+            zone_id,
+        }) => {
+            timestamp.hash(&mut hasher);
+            world_id.hash(&mut hasher);
+            instance_id.hash(&mut hasher);
+            experience_bonus.to_bits().hash(&mut hasher);
+            faction_nc.to_bits().hash(&mut hasher);
+            faction_tr.to_bits().hash(&mut hasher);
+            faction_vs.to_bits().hash(&mut hasher);
+            metagame_event_id.hash(&mut hasher);
+            metagame_event_state.hash(&mut hasher);
+            metagame_event_state_name.hash(&mut hasher);
+            zone_id.hash(&mut hasher);
+        }
+        Event::ItemAdded(event) => event.hash(&mut hasher),
+        Event::AchievementEarned(event) => event.hash(&mut hasher),
+        Event::SkillAdded(event) => event.hash(&mut hasher),
+        Event::BattleRankUp(event) => event.hash(&mut hasher),
+        Event::Unknown { raw, .. } => raw.to_string().hash(&mut hasher),
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Deduplicator;
+    use crate::WorldID;
+    use crate::realtime::event::{Event, PlayerLogin};
+    use chrono::{TimeZone, Utc};
+    use std::time::{Duration, Instant};
+
+    fn login(character_id: u64) -> Event {
+        Event::PlayerLogin(PlayerLogin {
+            character_id,
+            timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+            world_id: WorldID::Emerald,
+        })
+    }
+
+    #[test]
+    fn suppresses_duplicates_within_window() {
+        let mut dedup = Deduplicator::new(Duration::from_secs(10));
+        let now = Instant::now();
+
+        assert!(!dedup.is_duplicate(&login(1), now));
+        assert!(!dedup.is_duplicate(&login(2), now));
+        assert!(dedup.is_duplicate(&login(1), now + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn forgets_events_after_window() {
+        let mut dedup = Deduplicator::new(Duration::from_secs(10));
+        let now = Instant::now();
+
+        assert!(!dedup.is_duplicate(&login(1), now));
+        assert!(!dedup.is_duplicate(&login(1), now + Duration::from_secs(11)));
+    }
+}
@@ -1,13 +1,14 @@
 use crate::realtime::utils::*;
 use crate::{
-    CharacterID, ExperienceID, FacilityID, Faction, FiremodeID, Loadout, OutfitID, VehicleID,
-    WeaponID, WorldID, ZoneID,
+    AchievementID, AuraxisError, CharacterID, ExperienceID, FacilityID, Faction, FiremodeID,
+    Loadout, OutfitID, SkillID, VehicleID, WeaponID, WorldID, Zone, ZoneID,
 };
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use serde_with::{DeserializeAs, SerializeAs, TimestampMilliSeconds, TimestampSeconds};
+use serde_with::{DisplayFromStr, TimestampSeconds, serde_as};
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub enum EventNames {
@@ -34,44 +35,118 @@ impl serde::Serialize for EventNames {
     where
         S: serde::Serializer,
     {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for EventNames {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let event_name = String::deserialize(deserializer)?;
+
+        event_name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for EventNames {
+    type Err = AuraxisError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         use self::EventNames::*;
 
-        match *self {
-            AchievementEarned => {
-                serializer.serialize_unit_variant("EventNames", 0, "AchievementEarned")
-            }
-            BattleRankUp => serializer.serialize_unit_variant("EventNames", 1, "BattleRankUp"),
-            Death => serializer.serialize_unit_variant("EventNames", 2, "Death"),
-            ItemAdded => serializer.serialize_unit_variant("EventNames", 3, "ItemAdded"),
-            SkillAdded => serializer.serialize_unit_variant("EventNames", 4, "SkillAdded"),
-            VehicleDestroy => serializer.serialize_unit_variant("EventNames", 5, "VehicleDestroy"),
-            GainExperience => serializer.serialize_unit_variant("EventNames", 6, "GainExperience"),
-            GainExperienceId(value) => {
-                let event_name = format!("GainExperience_experience_id_{}", value);
-
-                serializer.serialize_str(&event_name)
-            }
-            PlayerFacilityCapture => {
-                serializer.serialize_unit_variant("EventNames", 8, "PlayerFacilityCapture")
-            }
-            PlayerFacilityDefend => {
-                serializer.serialize_unit_variant("EventNames", 9, "PlayerFacilityDefend")
-            }
-            ContinentLock => serializer.serialize_unit_variant("EventNames", 10, "ContinentLock"),
-            ContinentUnlock => {
-                serializer.serialize_unit_variant("EventNames", 11, "ContinentUnlock")
+        let unknown = || AuraxisError::UnknownEventName {
+            event_name: s.to_string(),
+        };
+
+        if let Some(experience_id) = s.strip_prefix("GainExperience_experience_id_") {
+            let experience_id = ExperienceID::from_str(experience_id).map_err(|_| unknown())?;
+            return Ok(GainExperienceId(experience_id));
+        }
+
+        let event_name = match s {
+            "AchievementEarned" => AchievementEarned,
+            "BattleRankUp" => BattleRankUp,
+            "Death" => Death,
+            "ItemAdded" => ItemAdded,
+            "SkillAdded" => SkillAdded,
+            "VehicleDestroy" => VehicleDestroy,
+            "GainExperience" => GainExperience,
+            "PlayerFacilityCapture" => PlayerFacilityCapture,
+            "PlayerFacilityDefend" => PlayerFacilityDefend,
+            "ContinentLock" => ContinentLock,
+            "ContinentUnlock" => ContinentUnlock,
+            "FacilityControl" => FacilityControl,
+            "MetagameEvent" => MetagameEvent,
+            "PlayerLogin" => PlayerLogin,
+            "PlayerLogout" => PlayerLogout,
+            _ => return Err(unknown()),
+        };
+
+        Ok(event_name)
+    }
+}
+
+impl Display for EventNames {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use self::EventNames::*;
+
+        match self {
+            AchievementEarned => write!(f, "AchievementEarned"),
+            BattleRankUp => write!(f, "BattleRankUp"),
+            Death => write!(f, "Death"),
+            ItemAdded => write!(f, "ItemAdded"),
+            SkillAdded => write!(f, "SkillAdded"),
+            VehicleDestroy => write!(f, "VehicleDestroy"),
+            GainExperience => write!(f, "GainExperience"),
+            GainExperienceId(experience_id) => {
+                write!(f, "GainExperience_experience_id_{experience_id}")
             }
-            FacilityControl => {
-                serializer.serialize_unit_variant("EventNames", 12, "FacilityControl")
+            PlayerFacilityCapture => write!(f, "PlayerFacilityCapture"),
+            PlayerFacilityDefend => write!(f, "PlayerFacilityDefend"),
+            ContinentLock => write!(f, "ContinentLock"),
+            ContinentUnlock => write!(f, "ContinentUnlock"),
+            FacilityControl => write!(f, "FacilityControl"),
+            MetagameEvent => write!(f, "MetagameEvent"),
+            PlayerLogin => write!(f, "PlayerLogin"),
+            PlayerLogout => write!(f, "PlayerLogout"),
+        }
+    }
+}
+
+impl EventNames {
+    /// Whether Census selects this event by world rather than by character.
+    pub fn is_world_event(&self) -> bool {
+        matches!(
+            self,
+            EventNames::ContinentLock
+                | EventNames::ContinentUnlock
+                | EventNames::FacilityControl
+                | EventNames::MetagameEvent
+        )
+    }
+
+    /// Whether a subscription to this event name delivers `event`.
+    ///
+    /// `GainExperience` matches every experience event,
+    /// `GainExperienceId` only those with its experience id.
+    pub fn matches(&self, event: &Event) -> bool {
+        match (self, event) {
+            (EventNames::GainExperienceId(experience_id), Event::GainExperience(event)) => {
+                event.experience_id == *experience_id
             }
-            MetagameEvent => serializer.serialize_unit_variant("EventNames", 13, "MetagameEvent"),
-            PlayerLogin => serializer.serialize_unit_variant("EventNames", 14, "PlayerLogin"),
-            PlayerLogout => serializer.serialize_unit_variant("EventNames", 15, "PlayerLogout"),
+            (name, event) => event.event_name().as_ref() == Some(name),
         }
     }
 }
 
-#[derive(Deserialize, PartialEq, Debug, Clone)]
+/// An ESS event.
+///
+/// Serializes to the payload sent by Census, so a serialized event
+/// deserializes back into the same event. See [`Event::to_clean_json`] for
+/// a form meant for other consumers.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(tag = "event_name")]
 pub enum Event {
     PlayerLogin(PlayerLogin),
@@ -86,11 +161,454 @@ pub enum Event {
     FacilityControl(FacilityControl),
     MetagameEvent(MetagameEvent),
     ItemAdded(ItemAdded),
+    AchievementEarned(AchievementEarned),
+    SkillAdded(SkillAdded),
+    BattleRankUp(BattleRankUp),
+    /// Any event this crate does not know how to parse yet, either because the
+    /// `event_name` is new or because the payload no longer matches its struct.
+    /// `raw` holds the complete payload as sent by Census.
+    #[serde(
+        untagged,
+        deserialize_with = "deserialize_unknown_event",
+        serialize_with = "serialize_unknown_event"
+    )]
+    Unknown {
+        event_name: String,
+        raw: serde_json::Value,
+    },
+}
+
+/// How important an event is to keep when events have to be dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Event {
+    /// High volume events that are individually cheap to lose are `Low`,
+    /// rare events describing the state of a continent or base are `High`.
+    pub fn priority(&self) -> EventPriority {
+        match self {
+            Event::GainExperience(_) | Event::ItemAdded(_) => EventPriority::Low,
+            Event::PlayerFacilityCapture(_)
+            | Event::PlayerFacilityDefend(_)
+            | Event::ContinentLock(_)
+            | Event::ContinentUnlock(_)
+            | Event::FacilityControl(_)
+            | Event::MetagameEvent(_) => EventPriority::High,
+            Event::PlayerLogin(_)
+            | Event::PlayerLogout(_)
+            | Event::Death(_)
+            | Event::VehicleDestroy(_)
+            | Event::AchievementEarned(_)
+            | Event::SkillAdded(_)
+            | Event::BattleRankUp(_)
+            | Event::Unknown { .. } => EventPriority::Normal,
+        }
+    }
+
+    pub fn world_id(&self) -> Option<WorldID> {
+        match self {
+            Event::PlayerLogin(event) => Some(event.world_id),
+            Event::PlayerLogout(event) => Some(event.world_id),
+            Event::Death(event) => Some(event.world_id),
+            Event::VehicleDestroy(event) => Some(event.world_id),
+            Event::GainExperience(event) => Some(event.world_id),
+            Event::PlayerFacilityCapture(event) => Some(event.world_id),
+            Event::PlayerFacilityDefend(event) => Some(event.world_id),
+            Event::ContinentLock(event) => Some(event.world_id),
+            Event::ContinentUnlock(event) => Some(event.world_id),
+            Event::FacilityControl(event) => Some(event.world_id),
+            Event::MetagameEvent(event) => Some(event.world_id),
+            Event::ItemAdded(event) => Some(event.world_id),
+            Event::AchievementEarned(event) => Some(event.world_id),
+            Event::SkillAdded(event) => Some(event.world_id),
+            Event::BattleRankUp(event) => Some(event.world_id),
+            Event::Unknown { .. } => None,
+        }
+    }
+
+    /// When Census says the event happened, `None` for unknown events.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Event::PlayerLogin(event) => Some(event.timestamp),
+            Event::PlayerLogout(event) => Some(event.timestamp),
+            Event::Death(event) => Some(event.timestamp),
+            Event::VehicleDestroy(event) => Some(event.timestamp),
+            Event::GainExperience(event) => Some(event.timestamp),
+            Event::PlayerFacilityCapture(event) => Some(event.timestamp),
+            Event::PlayerFacilityDefend(event) => Some(event.timestamp),
+            Event::ContinentLock(event) => Some(event.timestamp),
+            Event::ContinentUnlock(event) => Some(event.timestamp),
+            Event::FacilityControl(event) => Some(event.timestamp),
+            Event::MetagameEvent(event) => Some(event.timestamp),
+            Event::ItemAdded(event) => Some(event.timestamp),
+            Event::AchievementEarned(event) => Some(event.timestamp),
+            Event::SkillAdded(event) => Some(event.timestamp),
+            Event::BattleRankUp(event) => Some(event.timestamp),
+            Event::Unknown { .. } => None,
+        }
+    }
+
+    /// The zone the event happened in, `None` for login and logout events.
+    pub fn zone_id(&self) -> Option<ZoneID> {
+        match self {
+            Event::PlayerLogin(_) | Event::PlayerLogout(_) | Event::Unknown { .. } => None,
+            Event::Death(event) => Some(event.zone_id),
+            Event::VehicleDestroy(event) => Some(event.zone_id),
+            Event::GainExperience(event) => Some(event.zone_id),
+            Event::PlayerFacilityCapture(event) => Some(event.zone_id),
+            Event::PlayerFacilityDefend(event) => Some(event.zone_id),
+            Event::ContinentLock(event) => Some(event.zone_id),
+            Event::ContinentUnlock(event) => Some(event.zone_id),
+            Event::FacilityControl(event) => Some(event.zone_id),
+            Event::MetagameEvent(event) => Some(event.zone_id),
+            Event::ItemAdded(event) => Some(event.zone_id),
+            Event::AchievementEarned(event) => Some(event.zone_id),
+            Event::SkillAdded(event) => Some(event.zone_id),
+            Event::BattleRankUp(event) => Some(event.zone_id),
+        }
+    }
+
+    /// The decoded zone of the event, `None` for login and logout events.
+    pub fn zone(&self) -> Option<Zone> {
+        self.zone_id().map(Zone::from)
+    }
+
+    /// The character the event belongs to, `None` for world events.
+    pub fn character_id(&self) -> Option<CharacterID> {
+        match self {
+            Event::PlayerLogin(event) => Some(event.character_id),
+            Event::PlayerLogout(event) => Some(event.character_id),
+            Event::Death(event) => Some(event.character_id),
+            Event::VehicleDestroy(event) => Some(event.character_id),
+            Event::GainExperience(event) => Some(event.character_id),
+            Event::PlayerFacilityCapture(event) => Some(event.character_id),
+            Event::PlayerFacilityDefend(event) => Some(event.character_id),
+            Event::ItemAdded(event) => Some(event.character_id),
+            Event::AchievementEarned(event) => Some(event.character_id),
+            Event::SkillAdded(event) => Some(event.character_id),
+            Event::BattleRankUp(event) => Some(event.character_id),
+            Event::ContinentLock(_)
+            | Event::ContinentUnlock(_)
+            | Event::FacilityControl(_)
+            | Event::MetagameEvent(_)
+            | Event::Unknown { .. } => None,
+        }
+    }
+
+    /// The name this event is subscribed to with.
+    ///
+    /// Experience events map to `GainExperience`, see [`EventNames::matches`]
+    /// to also match `GainExperienceId`. Unknown events only have a name if
+    /// it is a known one whose payload failed to parse.
+    pub fn event_name(&self) -> Option<EventNames> {
+        match self {
+            Event::PlayerLogin(_) => Some(EventNames::PlayerLogin),
+            Event::PlayerLogout(_) => Some(EventNames::PlayerLogout),
+            Event::Death(_) => Some(EventNames::Death),
+            Event::VehicleDestroy(_) => Some(EventNames::VehicleDestroy),
+            Event::GainExperience(_) => Some(EventNames::GainExperience),
+            Event::PlayerFacilityCapture(_) => Some(EventNames::PlayerFacilityCapture),
+            Event::PlayerFacilityDefend(_) => Some(EventNames::PlayerFacilityDefend),
+            Event::ContinentLock(_) => Some(EventNames::ContinentLock),
+            Event::ContinentUnlock(_) => Some(EventNames::ContinentUnlock),
+            Event::FacilityControl(_) => Some(EventNames::FacilityControl),
+            Event::MetagameEvent(_) => Some(EventNames::MetagameEvent),
+            Event::ItemAdded(_) => Some(EventNames::ItemAdded),
+            Event::AchievementEarned(_) => Some(EventNames::AchievementEarned),
+            Event::SkillAdded(_) => Some(EventNames::SkillAdded),
+            Event::BattleRankUp(_) => Some(EventNames::BattleRankUp),
+            Event::Unknown { event_name, .. } => event_name.parse().ok(),
+        }
+    }
+
+    /// The attacking character of `Death` and `VehicleDestroy` events.
+    pub fn attacker_character_id(&self) -> Option<CharacterID> {
+        match self {
+            Event::Death(event) => Some(event.attacker_character_id),
+            Event::VehicleDestroy(event) => Some(event.attacker_character_id),
+            _ => None,
+        }
+    }
+
+    /// The event as plain JSON for consumers that do not want the string
+    /// encoded Census payload.
+    ///
+    /// Every field has one JSON type, picked by its Rust type: 64-bit ids such
+    /// as `character_id` and `outfit_id` are strings so JavaScript consumers do
+    /// not round them, smaller ids and counts are numbers, `timestamp` is in
+    /// milliseconds, `duration_held` in seconds and `is_headshot` a boolean.
+    /// Unknown events are returned as sent by Census. Unlike the Census payload
+    /// produced by `Serialize`, this form cannot be deserialized back.
+    pub fn to_clean_json(&self) -> serde_json::Value {
+        match self {
+            Event::PlayerLogin(event) => event.clean_json(),
+            Event::PlayerLogout(event) => event.clean_json(),
+            Event::Death(event) => event.clean_json(),
+            Event::VehicleDestroy(event) => event.clean_json(),
+            Event::GainExperience(event) => event.clean_json(),
+            Event::PlayerFacilityCapture(event) => event.clean_json(),
+            Event::PlayerFacilityDefend(event) => event.clean_json(),
+            Event::ContinentLock(event) => event.clean_json(),
+            Event::ContinentUnlock(event) => event.clean_json(),
+            Event::FacilityControl(event) => event.clean_json(),
+            Event::MetagameEvent(event) => event.clean_json(),
+            Event::ItemAdded(event) => event.clean_json(),
+            Event::AchievementEarned(event) => event.clean_json(),
+            Event::SkillAdded(event) => event.clean_json(),
+            Event::BattleRankUp(event) => event.clean_json(),
+            Event::Unknown { raw, .. } => raw.clone(),
+        }
+    }
+}
+
+macro_rules! impl_zone {
+    ($($event:ty),+ $(,)?) => {
+        $(
+            impl $event {
+                /// The zone of the event, decoded from `zone_id`.
+                pub fn zone(&self) -> Zone {
+                    Zone::from(self.zone_id)
+                }
+            }
+        )+
+    };
+}
+
+impl_zone!(
+    Death,
+    VehicleDestroy,
+    GainExperience,
+    PlayerFacilityCapture,
+    PlayerFacilityDefend,
+    ContinentLock,
+    ContinentUnlock,
+    FacilityControl,
+    MetagameEvent,
+    ItemAdded,
     AchievementEarned,
     SkillAdded,
     BattleRankUp,
+);
+
+/// How a payload field is written by [`Event::to_clean_json`].
+trait CleanValue {
+    fn clean_value(&self) -> serde_json::Value;
+}
+
+/// 64-bit ids do not fit a double, so they are always strings.
+impl CleanValue for u64 {
+    fn clean_value(&self) -> serde_json::Value {
+        self.to_string().into()
+    }
+}
+
+macro_rules! impl_clean_value_into {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl CleanValue for $ty {
+                fn clean_value(&self) -> serde_json::Value {
+                    self.clone().into()
+                }
+            }
+        )+
+    };
+}
+
+impl_clean_value_into!(u8, u16, u32, f32, bool, String);
+
+impl CleanValue for DateTime<Utc> {
+    fn clean_value(&self) -> serde_json::Value {
+        self.timestamp_millis().into()
+    }
+}
+
+impl CleanValue for Duration {
+    fn clean_value(&self) -> serde_json::Value {
+        self.num_seconds().into()
+    }
 }
 
+macro_rules! impl_clean_value_id {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl CleanValue for $ty {
+                fn clean_value(&self) -> serde_json::Value {
+                    u16::from(*self).into()
+                }
+            }
+        )+
+    };
+}
+
+impl_clean_value_id!(WorldID, Faction, Loadout);
+
+/// Destructures the payload so adding a field without listing it here fails
+/// to compile.
+macro_rules! impl_clean_json {
+    ($($event:ident { $($field:ident),+ $(,)? }),+ $(,)?) => {
+        $(
+            impl $event {
+                fn clean_json(&self) -> serde_json::Value {
+                    let $event { $($field),+ } = self;
+                    let mut payload = serde_json::Map::new();
+                    payload.insert(String::from("event_name"), stringify!($event).into());
+                    $(
+                        payload.insert(String::from(stringify!($field)), $field.clean_value());
+                    )+
+
+                    payload.into()
+                }
+            }
+        )+
+    };
+}
+
+impl_clean_json!(
+    PlayerLogin {
+        character_id,
+        timestamp,
+        world_id,
+    },
+    PlayerLogout {
+        character_id,
+        timestamp,
+        world_id,
+    },
+    Death {
+        attacker_character_id,
+        attacker_fire_mode_id,
+        attacker_loadout_id,
+        attacker_vehicle_id,
+        attacker_weapon_id,
+        character_id,
+        character_loadout_id,
+        is_headshot,
+        timestamp,
+        vehicle_id,
+        world_id,
+        zone_id,
+    },
+    VehicleDestroy {
+        attacker_character_id,
+        attacker_loadout_id,
+        attacker_vehicle_id,
+        attacker_weapon_id,
+        character_id,
+        facility_id,
+        faction_id,
+        timestamp,
+        vehicle_id,
+        world_id,
+        zone_id,
+    },
+    GainExperience {
+        character_id,
+        experience_id,
+        loadout_id,
+        other_id,
+        timestamp,
+        world_id,
+        zone_id,
+        amount,
+        team_id,
+    },
+    PlayerFacilityCapture {
+        character_id,
+        facility_id,
+        outfit_id,
+        timestamp,
+        world_id,
+        zone_id,
+    },
+    PlayerFacilityDefend {
+        character_id,
+        facility_id,
+        outfit_id,
+        timestamp,
+        world_id,
+        zone_id,
+    },
+    FacilityControl {
+        duration_held,
+        facility_id,
+        new_faction_id,
+        old_faction_id,
+        outfit_id,
+        timestamp,
+        world_id,
+        zone_id,
+    },
+    ContinentLock {
+        timestamp,
+        world_id,
+        zone_id,
+        triggering_faction,
+        previous_faction,
+        vs_population,
+        nc_population,
+        tr_population,
+        metagame_event_id,
+    },
+    ContinentUnlock {
+        timestamp,
+        world_id,
+        zone_id,
+        triggering_faction,
+        previous_faction,
+        vs_population,
+        nc_population,
+        tr_population,
+        metagame_event_id,
+    },
+    MetagameEvent {
+        timestamp,
+        world_id,
+        instance_id,
+        experience_bonus,
+        faction_nc,
+        faction_tr,
+        faction_vs,
+        metagame_event_id,
+        metagame_event_state,
+        metagame_event_state_name,
+        zone_id,
+    },
+    ItemAdded {
+        character_id,
+        context,
+        timestamp,
+        item_count,
+        item_id,
+        world_id,
+        zone_id,
+    },
+    AchievementEarned {
+        character_id,
+        achievement_id,
+        timestamp,
+        world_id,
+        zone_id,
+    },
+    SkillAdded {
+        character_id,
+        skill_id,
+        timestamp,
+        world_id,
+        zone_id,
+    },
+    BattleRankUp {
+        battle_rank,
+        character_id,
+        timestamp,
+        world_id,
+        zone_id,
+    },
+);
+
 impl Display for Event {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -130,285 +648,613 @@ impl Display for Event {
             Event::ItemAdded(_) => {
                 write!(f, "ItemAdded")
             }
-            Event::AchievementEarned => {
+            Event::AchievementEarned(_) => {
                 write!(f, "AchievementEarned")
             }
-            Event::SkillAdded => {
+            Event::SkillAdded(_) => {
                 write!(f, "SkillAdded")
             }
-            Event::BattleRankUp => {
+            Event::BattleRankUp(_) => {
                 write!(f, "BattleRankUp")
             }
+            Event::Unknown { event_name, .. } => {
+                write!(f, "{}", event_name)
+            }
         }
     }
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct PlayerLogin {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct PlayerLogout {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct Death {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_fire_mode_id: FiremodeID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub attacker_loadout_id: Loadout,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_vehicle_id: VehicleID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_weapon_id: WeaponID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub character_loadout_id: Loadout,
-    #[serde(deserialize_with = "de_bool_from_str_int")]
-    pub is_headshot: bool,
     #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
+        deserialize_with = "de_bool_from_str_int",
+        serialize_with = "ser_bool_to_str_int"
     )]
+    pub is_headshot: bool,
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(default, deserialize_with = "deserialize_from_str")]
+    #[serde(default)]
+    #[serde_as(as = "DisplayFromStr")]
     pub vehicle_id: VehicleID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct VehicleDestroy {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub attacker_loadout_id: Loadout,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_vehicle_id: VehicleID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub attacker_weapon_id: WeaponID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub facility_id: FacilityID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub faction_id: Faction,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub vehicle_id: VehicleID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct GainExperience {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub experience_id: ExperienceID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub loadout_id: Loadout,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub other_id: CharacterID,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub amount: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub team_id: Faction,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct PlayerFacilityCapture {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub facility_id: FacilityID,
+    #[serde_as(as = "DisplayFromStr")]
     pub outfit_id: OutfitID,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct PlayerFacilityDefend {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub facility_id: FacilityID,
+    #[serde_as(as = "DisplayFromStr")]
     pub outfit_id: OutfitID,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct FacilityControl {
     #[serde(
         deserialize_with = "deserialize_duration_from_str",
-        serialize_with = "serialize_duration"
+        serialize_with = "serialize_duration_to_str"
     )]
     pub duration_held: Duration,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub facility_id: FacilityID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub new_faction_id: Faction,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub old_faction_id: Faction,
+    #[serde_as(as = "DisplayFromStr")]
     pub outfit_id: OutfitID,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct ContinentLock {
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub triggering_faction: Faction,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub previous_faction: Faction,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub vs_population: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub nc_population: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub tr_population: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub metagame_event_id: u8,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct ContinentUnlock {
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub triggering_faction: Faction,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub previous_faction: Faction,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub vs_population: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub nc_population: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub tr_population: u16,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub metagame_event_id: u8,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct MetagameEvent {
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub instance_id: u32,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub experience_bonus: f32,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub faction_nc: f32,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub faction_tr: f32,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub faction_vs: f32,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub metagame_event_id: u8,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub metagame_event_state: u8,
     pub metagame_event_state_name: String,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
 
+#[serde_as]
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
 pub struct ItemAdded {
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub character_id: CharacterID,
     pub context: String,
-    #[serde(
-        deserialize_with = "TimestampSeconds::<String>::deserialize_as",
-        serialize_with = "TimestampMilliSeconds::<i64>::serialize_as"
-    )]
+    #[serde_as(as = "TimestampSeconds<String>")]
     pub timestamp: DateTime<Utc>,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub item_count: u8,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub item_id: u64,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "IdFromStr")]
+    pub world_id: WorldID,
+    #[serde_as(as = "DisplayFromStr")]
+    pub zone_id: ZoneID,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+pub struct AchievementEarned {
+    #[serde_as(as = "DisplayFromStr")]
+    pub character_id: CharacterID,
+    #[serde_as(as = "DisplayFromStr")]
+    pub achievement_id: AchievementID,
+    #[serde_as(as = "TimestampSeconds<String>")]
+    pub timestamp: DateTime<Utc>,
+    #[serde_as(as = "IdFromStr")]
+    pub world_id: WorldID,
+    #[serde_as(as = "DisplayFromStr")]
+    pub zone_id: ZoneID,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+pub struct SkillAdded {
+    #[serde_as(as = "DisplayFromStr")]
+    pub character_id: CharacterID,
+    #[serde_as(as = "DisplayFromStr")]
+    pub skill_id: SkillID,
+    #[serde_as(as = "TimestampSeconds<String>")]
+    pub timestamp: DateTime<Utc>,
+    #[serde_as(as = "IdFromStr")]
     pub world_id: WorldID,
-    #[serde(deserialize_with = "deserialize_from_str")]
+    #[serde_as(as = "DisplayFromStr")]
     pub zone_id: ZoneID,
 }
+
+#[serde_as]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Hash)]
+pub struct BattleRankUp {
+    #[serde_as(as = "DisplayFromStr")]
+    pub battle_rank: u8,
+    #[serde_as(as = "DisplayFromStr")]
+    pub character_id: CharacterID,
+    #[serde_as(as = "TimestampSeconds<String>")]
+    pub timestamp: DateTime<Utc>,
+    #[serde_as(as = "IdFromStr")]
+    pub world_id: WorldID,
+    #[serde_as(as = "DisplayFromStr")]
+    pub zone_id: ZoneID,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AchievementEarned, BattleRankUp, Event, EventNames, SkillAdded};
+    use crate::{AuraxisError, WorldID};
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    #[test]
+    fn deserializes_progression_events() {
+        let achievement = serde_json::from_str::<Event>(
+            r#"{
+                "achievement_id": "90039",
+                "character_id": "5428010618015189713",
+                "event_name": "AchievementEarned",
+                "timestamp": "1700000000",
+                "world_id": "17",
+                "zone_id": "2"
+            }"#,
+        )
+        .expect("achievement should deserialize");
+
+        assert_eq!(
+            achievement,
+            Event::AchievementEarned(AchievementEarned {
+                character_id: 5428010618015189713,
+                achievement_id: 90039,
+                timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+                world_id: WorldID::Emerald,
+                zone_id: 2,
+            })
+        );
+
+        let skill = serde_json::from_str::<Event>(
+            r#"{
+                "character_id": "5428010618015189713",
+                "event_name": "SkillAdded",
+                "skill_id": "7063",
+                "timestamp": "1700000000",
+                "world_id": "1",
+                "zone_id": "4"
+            }"#,
+        )
+        .expect("skill should deserialize");
+
+        assert_eq!(
+            skill,
+            Event::SkillAdded(SkillAdded {
+                character_id: 5428010618015189713,
+                skill_id: 7063,
+                timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+                world_id: WorldID::Connery,
+                zone_id: 4,
+            })
+        );
+
+        let battle_rank = serde_json::from_str::<Event>(
+            r#"{
+                "battle_rank": "42",
+                "character_id": "5428010618015189713",
+                "event_name": "BattleRankUp",
+                "timestamp": "1700000000",
+                "world_id": "10",
+                "zone_id": "6"
+            }"#,
+        )
+        .expect("battle rank should deserialize");
+
+        assert_eq!(
+            battle_rank,
+            Event::BattleRankUp(BattleRankUp {
+                battle_rank: 42,
+                character_id: 5428010618015189713,
+                timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+                world_id: WorldID::Miller,
+                zone_id: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_events_fall_back_to_raw_payload() {
+        let payload = json!({
+            "event_name": "FishScan",
+            "character_id": "5428010618015189713",
+            "fish_id": "12",
+            "world_id": "17"
+        });
+
+        let event = serde_json::from_value::<Event>(payload.clone())
+            .expect("unknown event should deserialize");
+
+        assert_eq!(
+            event,
+            Event::Unknown {
+                event_name: "FishScan".to_string(),
+                raw: payload,
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_known_events_fall_back_to_raw_payload() {
+        let payload = json!({
+            "event_name": "PlayerLogin",
+            "character_id": "5428010618015189713",
+            "timestamp": "1700000000",
+            "world_id": "not a world"
+        });
+
+        let event = serde_json::from_value::<Event>(payload.clone())
+            .expect("malformed event should deserialize");
+
+        assert_eq!(
+            event,
+            Event::Unknown {
+                event_name: "PlayerLogin".to_string(),
+                raw: payload,
+            }
+        );
+    }
+
+    #[test]
+    fn event_names_round_trip() {
+        for name in [
+            EventNames::Death,
+            EventNames::GainExperience,
+            EventNames::GainExperienceId(674),
+            EventNames::PlayerLogout,
+        ] {
+            let serialized = serde_json::to_string(&name).unwrap();
+            assert_eq!(serialized, format!("\"{name}\""));
+            assert_eq!(
+                serde_json::from_str::<EventNames>(&serialized).unwrap(),
+                name
+            );
+        }
+
+        assert_eq!(
+            "GainExperience_experience_id_7"
+                .parse::<EventNames>()
+                .unwrap(),
+            EventNames::GainExperienceId(7)
+        );
+        assert!(matches!(
+            "GainExperience_experience_id_".parse::<EventNames>(),
+            Err(AuraxisError::UnknownEventName { event_name })
+                if event_name == "GainExperience_experience_id_"
+        ));
+        assert!(matches!(
+            "FishScan".parse::<EventNames>(),
+            Err(AuraxisError::UnknownEventName { event_name }) if event_name == "FishScan"
+        ));
+    }
+
+    #[test]
+    fn events_match_their_names() {
+        let experience = serde_json::from_value::<Event>(json!({
+            "event_name": "GainExperience",
+            "character_id": "5428010618015189713",
+            "experience_id": "674",
+            "loadout_id": "4",
+            "other_id": "0",
+            "timestamp": "1700000000",
+            "world_id": "17",
+            "zone_id": "2",
+            "amount": "100",
+            "team_id": "2"
+        }))
+        .unwrap();
+
+        assert!(matches!(experience, Event::GainExperience(_)));
+        assert_eq!(experience.event_name(), Some(EventNames::GainExperience));
+        assert!(EventNames::GainExperience.matches(&experience));
+        assert!(EventNames::GainExperienceId(674).matches(&experience));
+        assert!(!EventNames::GainExperienceId(7).matches(&experience));
+        assert!(!EventNames::Death.matches(&experience));
+
+        let unknown = Event::Unknown {
+            event_name: "Death".to_string(),
+            raw: json!({}),
+        };
+        assert_eq!(unknown.event_name(), Some(EventNames::Death));
+    }
+
+    #[test]
+    fn serializes_census_payloads() {
+        let payloads = [
+            json!({"event_name": "PlayerLogin", "character_id": "5428010618015189713", "timestamp": "1700000000", "world_id": "17"}),
+            json!({"event_name": "PlayerLogout", "character_id": "5428010618015189713", "timestamp": "1700000000", "world_id": "17"}),
+            json!({"event_name": "Death", "attacker_character_id": "5428010618015189713", "attacker_fire_mode_id": "7", "attacker_loadout_id": "4", "attacker_vehicle_id": "0", "attacker_weapon_id": "80", "character_id": "5428010618015189714", "character_loadout_id": "15", "is_headshot": "1", "timestamp": "1700000000", "vehicle_id": "0", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "VehicleDestroy", "attacker_character_id": "5428010618015189713", "attacker_loadout_id": "4", "attacker_vehicle_id": "0", "attacker_weapon_id": "80", "character_id": "5428010618015189714", "facility_id": "0", "faction_id": "2", "timestamp": "1700000000", "vehicle_id": "4", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "GainExperience", "character_id": "5428010618015189713", "experience_id": "674", "loadout_id": "4", "other_id": "0", "timestamp": "1700000000", "world_id": "17", "zone_id": "2", "amount": "100", "team_id": "2"}),
+            json!({"event_name": "PlayerFacilityCapture", "character_id": "5428010618015189713", "facility_id": "222280", "outfit_id": "37509488620604883", "timestamp": "1700000000", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "PlayerFacilityDefend", "character_id": "5428010618015189713", "facility_id": "222280", "outfit_id": "0", "timestamp": "1700000000", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "ContinentLock", "timestamp": "1700000000", "world_id": "17", "zone_id": "2", "triggering_faction": "1", "previous_faction": "3", "vs_population": "40", "nc_population": "30", "tr_population": "30", "metagame_event_id": "147"}),
+            json!({"event_name": "ContinentUnlock", "timestamp": "1700000000", "world_id": "17", "zone_id": "2", "triggering_faction": "1", "previous_faction": "3", "vs_population": "40", "nc_population": "30", "tr_population": "30", "metagame_event_id": "147"}),
+            json!({"event_name": "FacilityControl", "duration_held": "3600", "facility_id": "222280", "new_faction_id": "1", "old_faction_id": "2", "outfit_id": "0", "timestamp": "1700000000", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "MetagameEvent", "timestamp": "1700000000", "world_id": "17", "instance_id": "12345", "experience_bonus": "25", "faction_nc": "33.5", "faction_tr": "33", "faction_vs": "33.5", "metagame_event_id": "147", "metagame_event_state": "135", "metagame_event_state_name": "started", "zone_id": "2"}),
+            json!({"event_name": "ItemAdded", "character_id": "5428010618015189713", "context": "GuildBankWithdrawal", "timestamp": "1700000000", "item_count": "1", "item_id": "6003", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "AchievementEarned", "character_id": "5428010618015189713", "achievement_id": "90039", "timestamp": "1700000000", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "SkillAdded", "character_id": "5428010618015189713", "skill_id": "1234", "timestamp": "1700000000", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "BattleRankUp", "battle_rank": "42", "character_id": "5428010618015189713", "timestamp": "1700000000", "world_id": "17", "zone_id": "2"}),
+            json!({"event_name": "FishScan", "character_id": "5428010618015189713", "fish_id": "12"}),
+        ];
+
+        for payload in payloads {
+            let event = serde_json::from_value::<Event>(payload.clone()).unwrap();
+            assert!(
+                !matches!(event, Event::Unknown { .. }) || event.to_string() == "FishScan",
+                "{payload} should deserialize into a known event"
+            );
+
+            assert_eq!(serde_json::to_value(&event).unwrap(), payload);
+            assert_eq!(
+                serde_json::from_str::<Event>(&serde_json::to_string(&event).unwrap()).unwrap(),
+                event
+            );
+        }
+    }
+
+    #[test]
+    fn cleans_json_for_other_consumers() {
+        let death = serde_json::from_value::<Event>(json!({
+            "event_name": "Death",
+            "attacker_character_id": "5428010618015189713",
+            "attacker_fire_mode_id": "7",
+            "attacker_loadout_id": "4",
+            "attacker_vehicle_id": "0",
+            "attacker_weapon_id": "80",
+            "character_id": "5428010618015189714",
+            "character_loadout_id": "15",
+            "is_headshot": "1",
+            "timestamp": "1700000000",
+            "vehicle_id": "0",
+            "world_id": "17",
+            "zone_id": "2"
+        }))
+        .unwrap();
+
+        let clean = death.to_clean_json();
+        assert_eq!(clean["event_name"], json!("Death"));
+        assert_eq!(clean["attacker_character_id"], json!("5428010618015189713"));
+        assert_eq!(clean["attacker_vehicle_id"], json!(0));
+        assert_eq!(clean["attacker_weapon_id"], json!(80));
+        assert_eq!(clean["is_headshot"], json!(true));
+        assert_eq!(clean["timestamp"], json!(1700000000000i64));
+        assert_eq!(clean["world_id"], json!(17));
+
+        let raw = json!({"event_name": "NewEvent", "character_id": "5428010618015189713"});
+        let unknown = serde_json::from_value::<Event>(raw.clone()).unwrap();
+        assert_eq!(unknown.to_clean_json(), raw);
+    }
+
+    #[test]
+    fn clean_json_types_do_not_depend_on_values() {
+        let experience = serde_json::from_value::<Event>(json!({
+            "event_name": "GainExperience",
+            "character_id": "5428010618015189713",
+            "experience_id": "674",
+            "loadout_id": "4",
+            "other_id": "0",
+            "timestamp": "1700000000",
+            "world_id": "17",
+            "zone_id": "2",
+            "amount": "100",
+            "team_id": "2"
+        }))
+        .unwrap();
+
+        let clean = experience.to_clean_json();
+        assert_eq!(clean["character_id"], json!("5428010618015189713"));
+        assert_eq!(clean["other_id"], json!("0"));
+        assert_eq!(clean["experience_id"], json!(674));
+        assert_eq!(clean["loadout_id"], json!(4));
+        assert_eq!(clean["team_id"], json!(2));
+        assert_eq!(clean["amount"], json!(100));
+
+        let capture = serde_json::from_value::<Event>(json!({
+            "event_name": "FacilityControl",
+            "duration_held": "3600",
+            "facility_id": "222280",
+            "new_faction_id": "2",
+            "old_faction_id": "3",
+            "outfit_id": "0",
+            "timestamp": "1700000000",
+            "world_id": "17",
+            "zone_id": "2"
+        }))
+        .unwrap();
+
+        let clean = capture.to_clean_json();
+        assert_eq!(clean["outfit_id"], json!("0"));
+        assert_eq!(clean["duration_held"], json!(3600));
+        assert_eq!(clean["new_faction_id"], json!(2));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::realtime::event::{Event, EventNames};
+use crate::realtime::subscription::{
+    CharacterSubscription, EventSubscription, SubscriptionSettings, WorldSubscription,
+};
+use crate::{CharacterID, Continent, WorldID};
+
+/// Selects the events delivered to a listener, see [`RealtimeClient::listen`].
+///
+/// Every criterion that is set must match. A character matches both as the
+/// character of the event and as the attacker of a `Death` or
+/// `VehicleDestroy`, and never matches world events. Zones match on their
+/// continent, so every instance of an instanced zone such as Koltyr matches.
+///
+/// [`RealtimeClient::listen`]: crate::realtime::client::RealtimeClient::listen
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    pub event_names: Option<Vec<EventNames>>,
+    pub worlds: Option<Vec<WorldID>>,
+    pub zones: Option<Vec<Continent>>,
+    pub characters: Option<Vec<CharacterID>>,
+}
+
+impl EventFilter {
+    /// A filter matching every event.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn event_names(mut self, event_names: impl IntoIterator<Item = EventNames>) -> Self {
+        self.event_names = Some(event_names.into_iter().collect());
+        self
+    }
+
+    #[must_use]
+    pub fn worlds(mut self, worlds: impl IntoIterator<Item = WorldID>) -> Self {
+        self.worlds = Some(worlds.into_iter().collect());
+        self
+    }
+
+    #[must_use]
+    pub fn zones(mut self, zones: impl IntoIterator<Item = Continent>) -> Self {
+        self.zones = Some(zones.into_iter().collect());
+        self
+    }
+
+    #[must_use]
+    pub fn characters(mut self, characters: impl IntoIterator<Item = CharacterID>) -> Self {
+        self.characters = Some(characters.into_iter().collect());
+        self
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        if let Some(event_names) = &self.event_names
+            && !event_names.iter().any(|name| name.matches(event))
+        {
+            return false;
+        }
+
+        if let Some(worlds) = &self.worlds
+            && !event
+                .world_id()
+                .is_some_and(|world| worlds.contains(&world))
+        {
+            return false;
+        }
+
+        if let Some(zones) = &self.zones
+            && !event
+                .zone()
+                .is_some_and(|zone| zones.contains(&zone.continent()))
+        {
+            return false;
+        }
+
+        if let Some(characters) = &self.characters
+            && ![event.character_id(), event.attacker_character_id()]
+                .into_iter()
+                .flatten()
+                .any(|character| characters.contains(&character))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// The Census subscription needed to receive every event matching this filter.
+    ///
+    /// Census cannot filter on zones, and combining characters with worlds
+    /// would affect the whole connection, so the subscription may be broader
+    /// than the filter. Without characters, every character is only
+    /// subscribed to when the filter selects character events.
+    pub fn subscription(&self) -> SubscriptionSettings {
+        let event_names = match &self.event_names {
+            Some(event_names) => EventSubscription::Ids(event_names.clone()),
+            None => EventSubscription::All,
+        };
+        let character_events = self.event_names.as_ref().is_none_or(|event_names| {
+            event_names
+                .iter()
+                .any(|event_name| !event_name.is_world_event())
+        });
+
+        let (characters, worlds) = match (&self.characters, &self.worlds) {
+            (Some(characters), _) => (Some(CharacterSubscription::Ids(characters.clone())), None),
+            (None, worlds) => (
+                character_events.then_some(CharacterSubscription::All),
+                Some(
+                    worlds
+                        .clone()
+                        .map_or(WorldSubscription::All, WorldSubscription::Ids),
+                ),
+            ),
+        };
+
+        SubscriptionSettings {
+            event_names: Some(event_names),
+            characters,
+            worlds,
+            ..SubscriptionSettings::empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventFilter;
+    use crate::realtime::event::{Death, Event, EventNames, PlayerLogin};
+    use crate::realtime::subscription::{CharacterSubscription, WorldSubscription};
+    use crate::{Continent, Loadout, WorldID};
+    use chrono::{TimeZone, Utc};
+
+    fn death(character_id: u64, attacker_character_id: u64, zone_id: u32) -> Event {
+        Event::Death(Death {
+            attacker_character_id,
+            attacker_fire_mode_id: 0,
+            attacker_loadout_id: Loadout::NCMedic,
+            attacker_vehicle_id: 0,
+            attacker_weapon_id: 0,
+            character_id,
+            character_loadout_id: Loadout::NCMedic,
+            is_headshot: false,
+            timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+            vehicle_id: 0,
+            world_id: WorldID::Emerald,
+            zone_id,
+        })
+    }
+
+    #[test]
+    fn matches_every_criterion() {
+        let filter = EventFilter::all()
+            .event_names([EventNames::Death])
+            .worlds([WorldID::Emerald])
+            .zones([Continent::Indar])
+            .characters([1]);
+
+        assert!(filter.matches(&death(1, 5, 2)));
+        assert!(filter.matches(&death(5, 1, 2)));
+        assert!(!filter.matches(&death(5, 6, 2)));
+        assert!(!filter.matches(&death(1, 5, 4)));
+        assert!(!filter.matches(&Event::PlayerLogin(PlayerLogin {
+            character_id: 1,
+            timestamp: Utc.timestamp_opt(1700000000, 0).unwrap(),
+            world_id: WorldID::Emerald,
+        })));
+        assert!(EventFilter::all().matches(&death(5, 6, 4)));
+    }
+
+    #[test]
+    fn zones_match_every_instance() {
+        let filter = EventFilter::all().zones([Continent::Desolation]);
+
+        assert!(filter.matches(&death(1, 5, 361)));
+        assert!(filter.matches(&death(1, 5, 0x0003_0169)));
+        assert!(!filter.matches(&death(1, 5, 0x0003_0002)));
+    }
+
+    #[test]
+    fn subscription_covers_filter() {
+        let characters = EventFilter::all()
+            .characters([1])
+            .worlds([WorldID::Emerald]);
+        let worlds = EventFilter::all().worlds([WorldID::Emerald]);
+
+        assert_eq!(
+            characters.subscription().characters,
+            Some(CharacterSubscription::Ids(vec![1]))
+        );
+        assert_eq!(characters.subscription().worlds, None);
+        assert_eq!(
+            worlds.subscription().characters,
+            Some(CharacterSubscription::All)
+        );
+        assert_eq!(
+            worlds.subscription().worlds,
+            Some(WorldSubscription::Ids(vec![WorldID::Emerald]))
+        );
+
+        let world_events = worlds.event_names([EventNames::MetagameEvent]);
+        assert_eq!(world_events.subscription().characters, None);
+        assert_eq!(
+            world_events.subscription().worlds,
+            Some(WorldSubscription::Ids(vec![WorldID::Emerald]))
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{AuraxisError, WorldID};
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A single Census push service endpoint, as reported in heartbeat and
+/// `serviceStateChanged` messages (e.g. `EventServerEndpoint_Emerald_17`).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ServiceEndpoint {
+    pub endpoint: String,
+    pub world: WorldID,
+    /// The world name as sent by Census, also for worlds this crate does not
+    /// know.
+    pub world_name: String,
+}
+
+impl FromStr for ServiceEndpoint {
+    type Err = AuraxisError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| AuraxisError::InvalidServiceEndpoint {
+            endpoint: s.to_string(),
+            reason,
+        };
+
+        let (rest, world_id) = s
+            .rsplit_once('_')
+            .ok_or_else(|| invalid(String::from("missing world id")))?;
+        let (endpoint, world_name) = rest
+            .split_once('_')
+            .ok_or_else(|| invalid(String::from("missing world name")))?;
+        let world = WorldID::from_str(world_id)
+            .map_err(|err| invalid(format!("invalid world id: {err}")))?;
+
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            world,
+            world_name: world_name.to_string(),
+        })
+    }
+}
+
+impl Display for ServiceEndpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}_{}_{}",
+            self.endpoint,
+            self.world_name,
+            u16::from(self.world)
+        )
+    }
+}
+
+/// Last known state of every Census push service endpoint.
+///
+/// Replaced wholesale by each heartbeat and patched by `serviceStateChanged`
+/// messages in between.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServiceHealth {
+    pub endpoints: HashMap<ServiceEndpoint, bool>,
+}
+
+impl ServiceHealth {
+    /// Whether every endpoint serving `world` is online. Returns `None` if
+    /// Census has not reported on the world yet.
+    pub fn is_online(&self, world: WorldID) -> Option<bool> {
+        self.endpoints
+            .iter()
+            .filter(|(endpoint, _)| endpoint.world == world)
+            .map(|(_, online)| *online)
+            .reduce(|all_online, online| all_online && online)
+    }
+
+    /// Worlds with at least one endpoint reported offline.
+    pub fn degraded_worlds(&self) -> Vec<WorldID> {
+        let mut worlds = Vec::new();
+        for (endpoint, online) in &self.endpoints {
+            if !online && !worlds.contains(&endpoint.world) {
+                worlds.push(endpoint.world);
+            }
+        }
+
+        worlds
+    }
+
+    pub(crate) fn set(&mut self, endpoint: ServiceEndpoint, online: bool) {
+        self.endpoints.insert(endpoint, online);
+    }
+}
+
+impl<'de> Deserialize<'de> for ServiceHealth {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let online = HashMap::<String, String>::deserialize(deserializer)?;
+        let mut endpoints = HashMap::with_capacity(online.len());
+
+        for (endpoint, state) in online {
+            let Ok(endpoint) = endpoint.parse::<ServiceEndpoint>() else {
+                tracing::debug!("Skipping unrecognised service endpoint {endpoint}");
+                continue;
+            };
+            let state = state.parse::<bool>().map_err(serde::de::Error::custom)?;
+
+            endpoints.insert(endpoint, state);
+        }
+
+        Ok(Self { endpoints })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ServiceEndpoint, ServiceHealth};
+    use crate::{AuraxisError, WorldID};
+
+    #[test]
+    fn parses_endpoint_keys() {
+        let endpoint = "EventServerEndpoint_Emerald_17"
+            .parse::<ServiceEndpoint>()
+            .expect("endpoint should parse");
+
+        assert_eq!(
+            endpoint,
+            ServiceEndpoint {
+                endpoint: "EventServerEndpoint".to_string(),
+                world: WorldID::Emerald,
+                world_name: "Emerald".to_string(),
+            }
+        );
+        assert_eq!(endpoint.to_string(), "EventServerEndpoint_Emerald_17");
+
+        let unknown = "EventServerEndpoint_Unreleased_9999"
+            .parse::<ServiceEndpoint>()
+            .expect("unknown world should parse");
+        assert_eq!(unknown.world, WorldID::from(9999));
+        assert_eq!(unknown.to_string(), "EventServerEndpoint_Unreleased_9999");
+        assert!(matches!(
+            "EventServerEndpoint_Emerald".parse::<ServiceEndpoint>(),
+            Err(AuraxisError::InvalidServiceEndpoint { .. })
+        ));
+    }
+
+    #[test]
+    fn heartbeat_reports_degraded_worlds() {
+        let health = serde_json::from_str::<ServiceHealth>(
+            r#"{
+                "EventServerEndpoint_Connery_1": "true",
+                "EventServerEndpoint_Emerald_17": "false",
+                "EventServerEndpoint_Unreleased_9999": "true"
+            }"#,
+        )
+        .expect("heartbeat should deserialize");
+
+        assert_eq!(health.endpoints.len(), 3);
+        assert_eq!(health.is_online(WorldID::Connery), Some(true));
+        assert_eq!(health.is_online(WorldID::from(9999)), Some(true));
+        assert_eq!(health.is_online(WorldID::Emerald), Some(false));
+        assert_eq!(health.is_online(WorldID::Miller), None);
+        assert_eq!(health.degraded_worlds(), vec![WorldID::Emerald]);
+    }
+}
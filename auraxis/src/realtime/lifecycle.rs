@@ -0,0 +1,203 @@
+use crate::realtime::Subscription;
+use crate::realtime::subscription::SubscriptionSettings;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tokio::sync::broadcast;
+
+/// Capacity of the lifecycle broadcast channel. Lifecycle events are rare, so
+/// a slow listener only misses events after falling this far behind.
+const LIFECYCLE_CHANNEL_CAPACITY: usize = 64;
+
+/// Changes in the state of the connection to the Census push service.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionEvent {
+    /// Census confirmed the connection and is ready to accept subscriptions.
+    Connected,
+    /// The websocket went down. Events are missed until the next `Connected`.
+    Disconnected {
+        reason: String,
+        close_code: Option<u16>,
+    },
+    /// A reconnect attempt has been scheduled.
+    Reconnecting { attempt: usize },
+    /// Census acknowledged a subscription.
+    Subscribed(Subscription),
+    /// An acknowledged subscription does not match the subscription of the
+    /// client, which resends it.
+    SubscriptionMismatch {
+        expected: SubscriptionSettings,
+        acknowledged: Subscription,
+    },
+    /// Every reconnect attempt failed and the client has stopped.
+    ReconnectExhausted,
+}
+
+#[derive(Debug)]
+pub(crate) struct ConnectionTracker {
+    events: broadcast::Sender<ConnectionEvent>,
+    disconnected: AtomicBool,
+    attempt: AtomicUsize,
+    close_frame: Mutex<Option<(Option<u16>, String)>>,
+}
+
+impl ConnectionTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            events: broadcast::channel(LIFECYCLE_CHANNEL_CAPACITY).0,
+            disconnected: AtomicBool::new(false),
+            attempt: AtomicUsize::new(0),
+            close_frame: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Forget a previous connection before a user initiated connect, so its
+    /// retries are treated as initial connect attempts again.
+    pub(crate) fn reset(&self) {
+        self.disconnected.store(false, Ordering::SeqCst);
+        self.attempt.store(0, Ordering::SeqCst);
+        *self
+            .close_frame
+            .lock()
+            .expect("connection tracker state poisoned") = None;
+    }
+
+    /// The websocket (re)connected. Census confirms separately with
+    /// `connectionStateChanged`, which is reported through [`Self::connected`].
+    pub(crate) fn established(&self) {
+        self.disconnected.store(false, Ordering::SeqCst);
+        self.attempt.store(0, Ordering::SeqCst);
+    }
+
+    pub(crate) fn connected(&self) {
+        self.emit(ConnectionEvent::Connected);
+    }
+
+    pub(crate) fn subscribed(&self, subscription: Subscription) {
+        self.emit(ConnectionEvent::Subscribed(subscription));
+    }
+
+    pub(crate) fn subscription_mismatch(
+        &self,
+        expected: SubscriptionSettings,
+        acknowledged: Subscription,
+    ) {
+        self.emit(ConnectionEvent::SubscriptionMismatch {
+            expected,
+            acknowledged,
+        });
+    }
+
+    /// Remember the close frame sent by the server so the following
+    /// disconnect can report why the connection went away.
+    pub(crate) fn closed(&self, close_code: u16, reason: String) {
+        *self
+            .close_frame
+            .lock()
+            .expect("connection tracker state poisoned") = Some((Some(close_code), reason));
+    }
+
+    /// Remember why the client gave up on a connection that is still open.
+    pub(crate) fn stale(&self, reason: String) {
+        *self
+            .close_frame
+            .lock()
+            .expect("connection tracker state poisoned") = Some((None, reason));
+    }
+
+    pub(crate) fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn disconnected(&self) {
+        self.disconnected.store(true, Ordering::SeqCst);
+        self.attempt.store(0, Ordering::SeqCst);
+        let close_frame = self
+            .close_frame
+            .lock()
+            .expect("connection tracker state poisoned")
+            .take();
+
+        let event = match close_frame {
+            Some((close_code, reason)) => ConnectionEvent::Disconnected { reason, close_code },
+            None => ConnectionEvent::Disconnected {
+                reason: String::from("Connection lost"),
+                close_code: None,
+            },
+        };
+        self.emit(event);
+    }
+
+    /// Called for every retry drawn from the reconnect schedule. Retries drawn
+    /// for the initial connect are not reconnects and are not reported.
+    pub(crate) fn reconnecting(&self) {
+        if !self.disconnected.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let attempt = self.attempt.fetch_add(1, Ordering::SeqCst) + 1;
+        self.emit(ConnectionEvent::Reconnecting { attempt });
+    }
+
+    pub(crate) fn exhausted(&self) {
+        if !self.disconnected.load(Ordering::SeqCst) {
+            return;
+        }
+
+        self.emit(ConnectionEvent::ReconnectExhausted);
+    }
+
+    fn emit(&self, event: ConnectionEvent) {
+        // No listeners is not an error, lifecycle events are opt-in.
+        let _ = self.events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConnectionEvent, ConnectionTracker};
+
+    #[test]
+    fn disconnect_reports_close_frame_and_counts_attempts() {
+        let tracker = ConnectionTracker::new();
+        let mut events = tracker.subscribe();
+
+        tracker.reconnecting();
+        tracker.established();
+        tracker.closed(1001, "Going away".to_string());
+        tracker.disconnected();
+        tracker.reconnecting();
+        tracker.reconnecting();
+        tracker.established();
+        tracker.connected();
+        tracker.disconnected();
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ConnectionEvent::Disconnected {
+                reason: "Going away".to_string(),
+                close_code: Some(1001),
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ConnectionEvent::Reconnecting { attempt: 1 }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ConnectionEvent::Reconnecting { attempt: 2 }
+        );
+        assert_eq!(events.try_recv().unwrap(), ConnectionEvent::Connected);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            ConnectionEvent::Disconnected {
+                reason: "Connection lost".to_string(),
+                close_code: None,
+            }
+        );
+    }
+}
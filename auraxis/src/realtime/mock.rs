@@ -0,0 +1,811 @@
+//! A local stand-in for the Census push service, for testing code built on
+//! [`RealtimeClient`](crate::realtime::client::RealtimeClient) without a
+//! service ID or network access.
+//!
+//! Enabled with the `mock` feature.
+
+use crate::realtime::client::RealtimeClientConfig;
+use crate::{AuraxisError, CharacterID};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Notify, broadcast};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::{WebSocketStream, accept_async};
+use tracing::debug;
+
+/// How the mock server behaves towards every connection.
+#[derive(Debug, Clone, Default)]
+pub struct MockServerConfig {
+    /// Send a heartbeat with every endpoint online at this interval.
+    pub heartbeat_interval: Option<Duration>,
+    /// Event payloads sent to a connection after its first subscription ack.
+    pub script: Vec<Value>,
+    /// Reply to `recentCharacterIds` and `recentCharacterIdsCount`.
+    pub recent_character_ids: Vec<CharacterID>,
+}
+
+/// A websocket server speaking the Census push protocol on localhost.
+///
+/// Every connection is greeted with `connectionStateChanged`, acknowledges
+/// `subscribe` and `clearSubscribe` with the resulting subscription, answers
+/// `echo` and the recent character actions, and records every action it
+/// receives. The server stops when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    accept: JoinHandle<()>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    config: MockServerConfig,
+    commands: broadcast::Sender<Command>,
+    actions: Mutex<Vec<Value>>,
+    connections: AtomicUsize,
+    active_connections: AtomicUsize,
+    changed: Notify,
+}
+
+impl Shared {
+    fn changed(&self) {
+        self.changed.notify_waiters();
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Command {
+    Send(String),
+    Close,
+}
+
+impl MockServer {
+    /// Start a server with the default [`MockServerConfig`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no local port can be bound.
+    pub async fn start() -> Result<Self, AuraxisError> {
+        Self::start_with(MockServerConfig::default()).await
+    }
+
+    /// # Errors
+    ///
+    /// This function will return an error if no local port can be bound.
+    pub async fn start_with(config: MockServerConfig) -> Result<Self, AuraxisError> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(anyhow::Error::from)?;
+        let addr = listener.local_addr().map_err(anyhow::Error::from)?;
+
+        let shared = Arc::new(Shared {
+            config,
+            commands: broadcast::channel(1000).0,
+            actions: Mutex::new(Vec::new()),
+            connections: AtomicUsize::new(0),
+            active_connections: AtomicUsize::new(0),
+            changed: Notify::new(),
+        });
+
+        let accept = tokio::spawn(accept_connections(listener, shared.clone()));
+
+        Ok(Self {
+            addr,
+            shared,
+            accept,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}/streaming", self.addr)
+    }
+
+    /// A client config pointing at this server.
+    pub fn client_config(&self) -> RealtimeClientConfig {
+        RealtimeClientConfig {
+            service_id: String::from("mock"),
+            realtime_url: Some(self.url()),
+            ..RealtimeClientConfig::default()
+        }
+    }
+
+    /// Send an event `payload` as a `serviceMessage` to every connection.
+    pub fn send_event(&self, payload: Value) {
+        self.send_raw(
+            json!({"payload": payload, "service": "event", "type": "serviceMessage"}).to_string(),
+        );
+    }
+
+    /// Send `text` as is to every connection.
+    pub fn send_raw(&self, text: impl Into<String>) {
+        let _ = self.shared.commands.send(Command::Send(text.into()));
+    }
+
+    /// Close every connection with a normal close frame.
+    pub fn disconnect_all(&self) {
+        let _ = self.shared.commands.send(Command::Close);
+    }
+
+    /// Every action received so far, across all connections.
+    pub fn actions(&self) -> Vec<Value> {
+        self.shared
+            .actions
+            .lock()
+            .expect("mock server state poisoned")
+            .clone()
+    }
+
+    /// Number of connections accepted so far.
+    pub fn connections(&self) -> usize {
+        self.shared.connections.load(Ordering::SeqCst)
+    }
+
+    pub fn active_connections(&self) -> usize {
+        self.shared.active_connections.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the received actions satisfy `condition`.
+    ///
+    /// Returns whether it was satisfied before `timeout`.
+    pub async fn wait_for_actions(
+        &self,
+        timeout: Duration,
+        condition: impl Fn(&[Value]) -> bool,
+    ) -> bool {
+        self.wait_until(timeout, || {
+            condition(
+                &self
+                    .shared
+                    .actions
+                    .lock()
+                    .expect("mock server state poisoned"),
+            )
+        })
+        .await
+    }
+
+    /// Wait until `count` connections have been accepted in total.
+    ///
+    /// Returns whether they were before `timeout`.
+    pub async fn wait_for_connections(&self, count: usize, timeout: Duration) -> bool {
+        self.wait_until(timeout, || self.connections() >= count)
+            .await
+    }
+
+    async fn wait_until(&self, timeout: Duration, condition: impl Fn() -> bool) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let changed = self.shared.changed.notified();
+                if condition() {
+                    return;
+                }
+                changed.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+async fn accept_connections(listener: TcpListener, shared: Arc<Shared>) {
+    // Dropping the set when the server is aborted closes every connection.
+    let mut connections = JoinSet::new();
+
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    debug!("Mock server failed to accept connection: {err}");
+                    continue;
+                }
+            },
+            Some(_) = connections.join_next() => continue,
+        };
+
+        connections.spawn(handle_connection(stream, shared.clone()));
+    }
+}
+
+async fn handle_connection(stream: TcpStream, shared: Arc<Shared>) {
+    let Ok(mut websocket) = accept_async(stream).await else {
+        debug!("Mock server websocket handshake failed");
+        return;
+    };
+    let mut commands = shared.commands.subscribe();
+
+    shared.connections.fetch_add(1, Ordering::SeqCst);
+    shared.active_connections.fetch_add(1, Ordering::SeqCst);
+    shared.changed();
+
+    serve(&mut websocket, &mut commands, &shared).await;
+
+    shared.active_connections.fetch_sub(1, Ordering::SeqCst);
+    shared.changed();
+}
+
+async fn serve(
+    websocket: &mut WebSocketStream<TcpStream>,
+    commands: &mut broadcast::Receiver<Command>,
+    shared: &Shared,
+) {
+    let greeting =
+        json!({"connected": "true", "service": "push", "type": "connectionStateChanged"});
+    if send(websocket, greeting).await.is_err() {
+        return;
+    }
+
+    let mut subscription = MockSubscription::default();
+    let mut script = Some(shared.config.script.clone());
+    let mut heartbeat = shared.config.heartbeat_interval.map(tokio::time::interval);
+
+    loop {
+        tokio::select! {
+            message = websocket.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => continue,
+                };
+                let Ok(action) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+
+                shared
+                    .actions
+                    .lock()
+                    .expect("mock server state poisoned")
+                    .push(action.clone());
+                shared.changed();
+
+                for reply in reply_to(&action, &mut subscription, &shared.config) {
+                    if send(websocket, reply).await.is_err() {
+                        return;
+                    }
+                }
+
+                let subscribed = matches!(action_name(&action), Some("subscribe"));
+                if subscribed && let Some(script) = script.take() {
+                    for payload in script {
+                        let message = json!({"payload": payload, "service": "event", "type": "serviceMessage"});
+                        if send(websocket, message).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+            command = commands.recv() => match command {
+                Ok(Command::Send(text)) => {
+                    if websocket.send(Message::Text(text.into())).await.is_err() {
+                        return;
+                    }
+                }
+                Ok(Command::Close) => {
+                    let _ = websocket
+                        .close(Some(CloseFrame {
+                            code: CloseCode::Normal,
+                            reason: "Mock server closed the connection".into(),
+                        }))
+                        .await;
+                    return;
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = tick(&mut heartbeat) => {
+                let heartbeat = json!({
+                    "online": {"EventServerEndpoint_Connery_1": "true"},
+                    "service": "event",
+                    "type": "heartbeat",
+                });
+                if send(websocket, heartbeat).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+async fn tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+async fn send(websocket: &mut WebSocketStream<TcpStream>, message: Value) -> Result<(), ()> {
+    websocket
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .map_err(|_| ())
+}
+
+fn action_name(action: &Value) -> Option<&str> {
+    action.get("action")?.as_str()
+}
+
+fn reply_to(
+    action: &Value,
+    subscription: &mut MockSubscription,
+    config: &MockServerConfig,
+) -> Vec<Value> {
+    match action_name(action) {
+        Some("subscribe") => {
+            subscription.subscribe(action);
+            vec![subscription.ack()]
+        }
+        Some("clearSubscribe") => {
+            subscription.clear(action);
+            vec![subscription.ack()]
+        }
+        Some("echo") => action.get("payload").cloned().into_iter().collect(),
+        Some("recentCharacterIds") => vec![json!({
+            "result": {
+                "character_id_list": config
+                    .recent_character_ids
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+            },
+            "service": "event",
+            "type": "serviceMessage",
+        })],
+        Some("recentCharacterIdsCount") => vec![json!({
+            "result": {"count": config.recent_character_ids.len().to_string()},
+            "service": "event",
+            "type": "serviceMessage",
+        })],
+        _ => Vec::new(),
+    }
+}
+
+/// The subscription of one connection, kept as the strings sent by the client.
+#[derive(Debug, Default)]
+struct MockSubscription {
+    event_names: BTreeSet<String>,
+    characters: BTreeSet<String>,
+    worlds: BTreeSet<String>,
+    logical_and_characters_with_worlds: bool,
+}
+
+impl MockSubscription {
+    fn subscribe(&mut self, action: &Value) {
+        self.event_names.extend(strings(action, "eventNames"));
+        self.characters.extend(strings(action, "characters"));
+        self.worlds.extend(strings(action, "worlds"));
+
+        if let Some(logical_and) = action.get("logicalAndCharactersWithWorlds") {
+            self.logical_and_characters_with_worlds =
+                logical_and == &json!(true) || logical_and == &json!("true");
+        }
+    }
+
+    fn clear(&mut self, action: &Value) {
+        if action.get("all") == Some(&json!("true")) {
+            *self = Self::default();
+            return;
+        }
+
+        for event_name in strings(action, "eventNames") {
+            self.event_names.remove(&event_name);
+        }
+        for character in strings(action, "characters") {
+            self.characters.remove(&character);
+        }
+        for world in strings(action, "worlds") {
+            self.worlds.remove(&world);
+        }
+    }
+
+    fn ack(&self) -> Value {
+        json!({
+            "subscription": {
+                "characterCount": self.characters.iter().filter(|id| *id != "all").count(),
+                "eventNames": self.event_names,
+                "logicalAndCharactersWithWorlds": self.logical_and_characters_with_worlds,
+                "worlds": self.worlds,
+            }
+        })
+    }
+}
+
+fn strings(action: &Value, key: &str) -> Vec<String> {
+    action
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockServer, MockServerConfig, action_name};
+    use crate::WorldID;
+    use crate::realtime::client::RealtimeClient;
+    use crate::realtime::event::{Event, EventNames};
+    use crate::realtime::filter::EventFilter;
+    use crate::realtime::lifecycle::ConnectionEvent;
+    use crate::realtime::reconnect::ReconnectPolicy;
+    use crate::realtime::subscription::{
+        CharacterSubscription, EventSubscription, SubscriptionSettings, WorldSubscription,
+    };
+    use serde_json::json;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn subscription() -> SubscriptionSettings {
+        SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![EventNames::PlayerLogin])),
+            characters: Some(CharacterSubscription::Ids(vec![1])),
+            worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
+            ..SubscriptionSettings::empty()
+        }
+    }
+
+    #[tokio::test]
+    async fn delivers_scripted_events_and_acks_subscriptions() {
+        let server = MockServer::start_with(MockServerConfig {
+            script: vec![json!({
+                "character_id": "1",
+                "event_name": "PlayerLogin",
+                "timestamp": "1700000000",
+                "world_id": "17",
+            })],
+            ..MockServerConfig::default()
+        })
+        .await
+        .unwrap();
+
+        let mut client = RealtimeClient::new(server.client_config());
+        let mut lifecycle = client.connection_events();
+        let _subscription = client.subscribe(subscription());
+        let mut events = client.connect().await.unwrap();
+
+        let event = tokio::time::timeout(TIMEOUT, events.recv()).await.unwrap();
+        assert!(matches!(event, Some(Event::PlayerLogin(login)) if login.character_id == 1));
+
+        let acked = tokio::time::timeout(TIMEOUT, async {
+            loop {
+                if let Ok(ConnectionEvent::Subscribed(subscription)) = lifecycle.recv().await {
+                    return subscription;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(acked.character_count, 1);
+        assert_eq!(acked.event_names, vec!["PlayerLogin"]);
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn client_resubscribes_after_reconnect() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(crate::realtime::client::RealtimeClientConfig {
+            reconnect: ReconnectPolicy {
+                initial_delay: Duration::from_millis(10),
+                jitter: 0.0,
+                ..ReconnectPolicy::default()
+            },
+            ..server.client_config()
+        });
+        let _subscription = client.subscribe(subscription());
+        let _events = client.connect().await.unwrap();
+
+        let subscribes = |count: usize| {
+            move |actions: &[serde_json::Value]| {
+                actions
+                    .iter()
+                    .filter(|action| action_name(action) == Some("subscribe"))
+                    .count()
+                    >= count
+            }
+        };
+
+        assert!(server.wait_for_actions(TIMEOUT, subscribes(1)).await);
+        server.disconnect_all();
+        assert!(server.wait_for_connections(2, TIMEOUT).await);
+        assert!(server.wait_for_actions(TIMEOUT, subscribes(2)).await);
+
+        client.clear_all_subscriptions();
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| {
+                    actions.iter().any(|action| {
+                        action_name(action) == Some("clearSubscribe")
+                            && action.get("all") == Some(&json!("true"))
+                    })
+                })
+                .await
+        );
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reconnecting_after_disconnect_is_not_a_retry() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        let mut lifecycle = client.connection_events();
+
+        let _events = client.connect().await.unwrap();
+        client.disconnect().await.unwrap();
+        let _events = client.connect().await.unwrap();
+        assert!(server.wait_for_connections(2, TIMEOUT).await);
+        client.disconnect().await.unwrap();
+
+        let mut events = Vec::new();
+        while let Ok(event) = lifecycle.try_recv() {
+            events.push(event);
+        }
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, ConnectionEvent::Reconnecting { .. })),
+            "{events:?}"
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, ConnectionEvent::Disconnected { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn echoes_are_matched_by_payload() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        let _events = client.connect().await.unwrap();
+        assert!(server.wait_for_connections(1, TIMEOUT).await);
+
+        let (first, second) = tokio::join!(
+            client.echo(json!({"probe": 1}), TIMEOUT),
+            client.echo(json!({"probe": 2}), TIMEOUT),
+        );
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn mismatching_acks_are_resent() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        let _subscription = client.subscribe(subscription());
+        let _events = client.connect().await.unwrap();
+
+        let subscribes = |count: usize| {
+            move |actions: &[serde_json::Value]| {
+                actions
+                    .iter()
+                    .filter(|action| action_name(action) == Some("subscribe"))
+                    .count()
+                    >= count
+            }
+        };
+        assert!(server.wait_for_actions(TIMEOUT, subscribes(1)).await);
+
+        server.send_raw(
+            json!({"subscription": {
+                "characterCount": 1,
+                "eventNames": [],
+                "logicalAndCharactersWithWorlds": false,
+                "worlds": ["17"],
+            }})
+            .to_string(),
+        );
+        assert!(server.wait_for_actions(TIMEOUT, subscribes(2)).await);
+        assert_eq!(
+            server.actions().last(),
+            Some(
+                &json!({"action": "subscribe", "eventNames": ["PlayerLogin"], "service": "event"})
+            )
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let acknowledged = client.acknowledged_subscription().unwrap();
+        assert_eq!(acknowledged.event_names, vec!["PlayerLogin"]);
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unexpected_acked_entries_are_cleared() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        let _subscription = client.subscribe(subscription());
+        let _events = client.connect().await.unwrap();
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| !actions.is_empty())
+                .await
+        );
+
+        server.send_raw(
+            json!({"subscription": {
+                "characterCount": 1,
+                "eventNames": ["PlayerLogin"],
+                "logicalAndCharactersWithWorlds": false,
+                "worlds": ["1", "17"],
+            }})
+            .to_string(),
+        );
+        let clear = json!({"action": "clearSubscribe", "worlds": ["1"], "service": "event"});
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| actions.contains(&clear))
+                .await
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let actions = server.actions();
+        assert_eq!(actions.last(), Some(&clear));
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|action| action_name(action) == Some("subscribe"))
+                .count(),
+            1
+        );
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropping_a_handle_only_clears_unshared_entries() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        let shared = client.subscribe(subscription());
+        let other = client.subscribe(SubscriptionSettings {
+            characters: Some(CharacterSubscription::Ids(vec![1, 2])),
+            ..subscription()
+        });
+        let _events = client.connect().await.unwrap();
+
+        let clears = |actions: &[serde_json::Value]| {
+            actions
+                .iter()
+                .filter(|action| action_name(action) == Some("clearSubscribe"))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        drop(other);
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| !clears(actions).is_empty())
+                .await
+        );
+        assert_eq!(
+            clears(&server.actions()),
+            vec![json!({"action": "clearSubscribe", "characters": ["2"], "service": "event"})]
+        );
+
+        shared.cancel();
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| clears(actions).len() == 2)
+                .await
+        );
+        assert_eq!(
+            clears(&server.actions())[1],
+            json!({
+                "action": "clearSubscribe",
+                "eventNames": ["PlayerLogin"],
+                "characters": ["1"],
+                "worlds": ["17"],
+                "service": "event",
+            })
+        );
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropping_a_listener_releases_its_subscription() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        let _subscription = client.subscribe(subscription());
+        let _events = client.connect().await.unwrap();
+
+        let listener = client.listen(
+            EventFilter::all()
+                .event_names([EventNames::PlayerLogin])
+                .characters([2]),
+        );
+        let subscribed = json!({
+            "action": "subscribe",
+            "eventNames": ["PlayerLogin"],
+            "characters": ["1", "2"],
+            "worlds": ["17"],
+            "service": "event",
+        });
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| actions.contains(&subscribed))
+                .await
+        );
+
+        drop(listener);
+        let clear = json!({"action": "clearSubscribe", "characters": ["2"], "service": "event"});
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| actions.contains(&clear))
+                .await
+        );
+
+        client.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn set_subscription_subscribes_before_clearing() {
+        let server = MockServer::start().await.unwrap();
+        let mut client = RealtimeClient::new(server.client_config());
+        client.set_subscription(subscription());
+        let _events = client.connect().await.unwrap();
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| !actions.is_empty())
+                .await
+        );
+
+        client.set_subscription(SubscriptionSettings {
+            characters: Some(CharacterSubscription::Ids(vec![2])),
+            ..subscription()
+        });
+        let cleared = |actions: &[serde_json::Value]| {
+            actions
+                .iter()
+                .position(|action| action_name(action) == Some("clearSubscribe"))
+        };
+        assert!(
+            server
+                .wait_for_actions(TIMEOUT, |actions| cleared(actions).is_some())
+                .await
+        );
+
+        // The acknowledgements of the partial updates must not trigger a resend.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let actions = server.actions();
+        let clear = cleared(&actions).unwrap();
+        assert_eq!(
+            actions[clear - 1],
+            json!({"action": "subscribe", "characters": ["2"], "service": "event"})
+        );
+        assert_eq!(
+            actions[clear],
+            json!({"action": "clearSubscribe", "characters": ["1"], "service": "event"})
+        );
+        assert_eq!(actions.len(), clear + 1);
+
+        client.disconnect().await.unwrap();
+    }
+}
@@ -1,12 +1,25 @@
+pub mod backpressure;
 pub mod client;
+mod dedup;
 pub mod event;
+pub mod filter;
+pub mod health;
+pub mod lifecycle;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod pool;
+pub mod reconnect;
+pub mod recording;
 pub mod subscription;
 mod utils;
+pub mod watchdog;
 
+use crate::CharacterID;
 use event::Event;
+use health::{ServiceEndpoint, ServiceHealth};
 use serde;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_with::{DisplayFromStr, PickFirst, serde_as};
 use subscription::SubscriptionSettings;
 use subscription::{CharacterSubscription, EventSubscription, WorldSubscription};
 use utils::{deserialize_from_str, serialize_optional_bool};
@@ -51,15 +64,33 @@ enum Action {
     },
 }
 
-#[derive(Deserialize, PartialEq, Debug)]
+/// A subscription as acknowledged by Census.
+#[derive(Deserialize, PartialEq, Eq, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct Subscription {
+pub struct Subscription {
     pub character_count: u64,
     pub event_names: Vec<String>,
     pub logical_and_characters_with_worlds: bool,
     pub worlds: Vec<String>,
 }
 
+/// Reply to the `recentCharacterIds` and `recentCharacterIdsCount` actions.
+#[serde_as]
+#[derive(Deserialize, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+enum RecentCharacters {
+    Ids {
+        #[serde(alias = "recent_character_id_list")]
+        #[serde_as(as = "Vec<PickFirst<(_, DisplayFromStr)>>")]
+        character_id_list: Vec<CharacterID>,
+    },
+    Count {
+        #[serde(alias = "recent_character_id_count")]
+        #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+        count: u64,
+    },
+}
+
 #[derive(Deserialize, PartialEq, Debug)]
 #[serde(tag = "type", rename_all = "camelCase")]
 #[allow(clippy::enum_variant_names)]
@@ -69,8 +100,7 @@ enum Message {
         connected: bool,
     },
     Heartbeat {
-        // TODO: EventServerEndpoint / WorldId / request::WorldIds -> bool
-        online: HashMap<String, String>,
+        online: ServiceHealth,
     },
     ServiceMessage {
         payload: Event,
@@ -78,10 +108,89 @@ enum Message {
     ServiceStateChanged {
         #[serde(deserialize_with = "deserialize_from_str")]
         online: bool,
-        // TODO: EventServerEndpoint / WorldId / request::WorldIds
-        detail: String,
+        #[serde(deserialize_with = "deserialize_from_str")]
+        detail: ServiceEndpoint,
     },
     Subscription {
         subscription: Subscription,
     },
+    /// Census acknowledges subscriptions without a `type`.
+    #[serde(untagged)]
+    SubscriptionAck {
+        subscription: Subscription,
+    },
+    #[serde(untagged)]
+    RecentCharacters {
+        #[serde(alias = "payload")]
+        result: RecentCharacters,
+    },
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Message, RecentCharacters, Subscription};
+
+    #[test]
+    fn deserializes_recent_character_replies() {
+        let ids = serde_json::from_str::<Message>(
+            r#"{
+                "result": {"character_id_list": ["5428010618015189713", "5428010618020694593"]},
+                "service": "event",
+                "type": "serviceMessage"
+            }"#,
+        )
+        .expect("recent character ids should deserialize");
+
+        assert_eq!(
+            ids,
+            Message::RecentCharacters {
+                result: RecentCharacters::Ids {
+                    character_id_list: vec![5428010618015189713, 5428010618020694593],
+                },
+            }
+        );
+
+        let count = serde_json::from_str::<Message>(
+            r#"{
+                "payload": {"recent_character_id_count": 3044},
+                "service": "event",
+                "type": "serviceMessage"
+            }"#,
+        )
+        .expect("recent character count should deserialize");
+
+        assert_eq!(
+            count,
+            Message::RecentCharacters {
+                result: RecentCharacters::Count { count: 3044 },
+            }
+        );
+    }
+
+    #[test]
+    fn deserializes_untyped_subscription_acks() {
+        let ack = serde_json::from_str::<Message>(
+            r#"{"subscription": {
+                "characterCount": 1,
+                "eventNames": ["PlayerLogin"],
+                "logicalAndCharactersWithWorlds": false,
+                "worlds": ["17"]
+            }}"#,
+        )
+        .expect("subscription ack should deserialize");
+
+        assert_eq!(
+            ack,
+            Message::SubscriptionAck {
+                subscription: Subscription {
+                    character_count: 1,
+                    event_names: vec![String::from("PlayerLogin")],
+                    logical_and_characters_with_worlds: false,
+                    worlds: vec![String::from("17")],
+                },
+            }
+        );
+    }
 }
@@ -0,0 +1,310 @@
+use crate::realtime::backpressure::{EventSink, OverflowPolicy};
+use crate::realtime::client::{RealtimeClient, RealtimeClientConfig};
+use crate::realtime::dedup::Deduplicator;
+use crate::realtime::event::Event;
+use crate::realtime::subscription::{
+    CharacterSubscription, SubscriptionHandle, SubscriptionSettings,
+};
+use crate::{AuraxisError, CharacterID};
+
+use metrics::counter;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc::Receiver;
+use tokio::task::JoinSet;
+use tracing::debug;
+
+/// A set of realtime connections sharing one merged event stream.
+///
+/// Character subscriptions are split across the shards by character ID, so a
+/// large subscription is spread over several small websockets that reconnect
+/// and resubscribe independently. Event names apply to every shard, while
+/// world and all-character subscriptions are only sent to the first shard to
+/// avoid receiving those events once per shard.
+///
+/// An event can still arrive twice when it matches a character on one shard
+/// and a world on the first shard. Set `dedup_window` in the config to drop
+/// these, duplicates are detected on the merged stream. `event_channel_capacity`
+/// and `overflow_policy` also apply to the merged stream.
+#[derive(Debug, Clone)]
+pub struct RealtimeClientPool {
+    config: RealtimeClientConfig,
+    shards: Vec<RealtimeClient>,
+}
+
+impl RealtimeClientPool {
+    #[must_use]
+    pub fn new(config: RealtimeClientConfig, shard_count: usize) -> Self {
+        // Shards hand their events straight to the merged stream, which
+        // deduplicates and applies the overflow policy.
+        let shard_config = RealtimeClientConfig {
+            dedup_window: None,
+            overflow_policy: OverflowPolicy::Block,
+            ..config.clone()
+        };
+        let shards = (0..shard_count.max(1))
+            .map(|_| RealtimeClient::new(shard_config.clone()))
+            .collect();
+
+        Self { config, shards }
+    }
+
+    /// The underlying connections, e.g. to watch their health or lifecycle.
+    pub fn shards(&self) -> &[RealtimeClient] {
+        &self.shards
+    }
+
+    /// The index of the shard responsible for `character_id`.
+    pub fn shard_for(&self, character_id: CharacterID) -> usize {
+        (character_id % self.shards.len() as u64) as usize
+    }
+
+    /// Connect every shard and merge their events into one receiver.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any shard fails to connect, after
+    /// disconnecting the shards that did connect.
+    pub async fn connect(&mut self) -> Result<Receiver<Event>, AuraxisError> {
+        let (events, events_rx) = EventSink::channel(
+            self.config.event_channel_capacity,
+            self.config.overflow_policy,
+        );
+        let dedup = self
+            .config
+            .dedup_window
+            .map(|window| Arc::new(Mutex::new(Deduplicator::new(window))));
+
+        let mut forwarders = JoinSet::new();
+        for index in 0..self.shards.len() {
+            let shard_events = match self.shards[index].connect().await {
+                Ok(shard_events) => shard_events,
+                Err(err) => {
+                    for shard in &mut self.shards[..index] {
+                        if let Err(err) = shard.disconnect().await {
+                            debug!("Failed to disconnect realtime pool shard: {err}");
+                        }
+                    }
+                    return Err(err);
+                }
+            };
+
+            forwarders.spawn(forward_shard(
+                index,
+                shard_events,
+                events.clone(),
+                dedup.clone(),
+            ));
+        }
+
+        tokio::spawn(async move {
+            let shards_done = async { while forwarders.join_next().await.is_some() {} };
+            match events {
+                EventSink::Queue(queue) => {
+                    tokio::select! {
+                        _ = shards_done => {}
+                        _ = queue.forward() => debug!("Realtime pool consumer closed"),
+                    }
+                }
+                EventSink::Channel(_) => shards_done.await,
+            }
+        });
+
+        Ok(events_rx)
+    }
+
+    /// Disconnect every shard, see [`RealtimeClient::disconnect`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error returned by a shard.
+    pub async fn disconnect(&mut self) -> Result<(), AuraxisError> {
+        let mut result = Ok(());
+        for shard in &mut self.shards {
+            if let Err(err) = shard.disconnect().await
+                && result.is_ok()
+            {
+                result = Err(err);
+            }
+        }
+
+        result
+    }
+
+    /// Subscribe the shards, see [`RealtimeClient::subscribe`]. The handle
+    /// releases the subscription on every shard.
+    pub fn subscribe(&mut self, subscription: SubscriptionSettings) -> SubscriptionHandle {
+        let shards = split_subscription(&subscription, self.shards.len(), false);
+        let handles = self
+            .shards
+            .iter_mut()
+            .zip(shards)
+            .filter(|(_, subscription)| !subscription.is_empty())
+            .map(|(shard, subscription)| shard.subscribe(subscription))
+            .collect::<Vec<_>>();
+
+        SubscriptionHandle::join(handles)
+    }
+
+    pub fn clear_subscribe(&mut self, subscription: SubscriptionSettings) {
+        let shards = split_subscription(&subscription, self.shards.len(), true);
+        for (shard, subscription) in self.shards.iter_mut().zip(shards) {
+            if !subscription.is_empty() {
+                shard.clear_subscribe(subscription);
+            }
+        }
+    }
+
+    pub fn clear_all_subscriptions(&mut self) {
+        for shard in &mut self.shards {
+            shard.clear_all_subscriptions();
+        }
+    }
+}
+
+/// Move the events of one shard into the merged stream.
+async fn forward_shard(
+    index: usize,
+    mut shard_events: Receiver<Event>,
+    events: EventSink,
+    dedup: Option<Arc<Mutex<Deduplicator>>>,
+) {
+    while let Some(event) = shard_events.recv().await {
+        if let Some(dedup) = &dedup
+            && dedup
+                .lock()
+                .expect("realtime pool dedup state poisoned")
+                .is_duplicate(&event, Instant::now())
+        {
+            counter!("realtime_events_duplicates_suppressed").increment(1);
+            continue;
+        }
+
+        if events.send(event).await.is_err() {
+            debug!("Realtime pool consumer closed, stopping shard {index}");
+            return;
+        }
+    }
+}
+
+/// Split `subscription` into one subscription per shard.
+///
+/// When `clearing`, world and all-character entries are sent to every shard
+/// so that they are removed wherever they ended up.
+fn split_subscription(
+    subscription: &SubscriptionSettings,
+    shard_count: usize,
+    clearing: bool,
+) -> Vec<SubscriptionSettings> {
+    let mut shards = vec![
+        SubscriptionSettings {
+            event_names: subscription.event_names.clone(),
+            logical_and_characters_with_worlds: subscription.logical_and_characters_with_worlds,
+            service: subscription.service.clone(),
+            ..SubscriptionSettings::empty()
+        };
+        shard_count
+    ];
+
+    // With logical AND the worlds filter the characters of every shard.
+    let worlds_on_every_shard =
+        clearing || subscription.logical_and_characters_with_worlds == Some(true);
+    for (index, shard) in shards.iter_mut().enumerate() {
+        if index == 0 || worlds_on_every_shard {
+            shard.worlds = subscription.worlds.clone();
+        }
+    }
+
+    match &subscription.characters {
+        Some(CharacterSubscription::Ids(ids)) => {
+            for id in ids {
+                let shard = &mut shards[(*id % shard_count as u64) as usize];
+                match &mut shard.characters {
+                    Some(CharacterSubscription::Ids(shard_ids)) => shard_ids.push(*id),
+                    _ => shard.characters = Some(CharacterSubscription::Ids(vec![*id])),
+                }
+            }
+        }
+        Some(CharacterSubscription::All) => {
+            for (index, shard) in shards.iter_mut().enumerate() {
+                if index == 0 || clearing {
+                    shard.characters = Some(CharacterSubscription::All);
+                }
+            }
+        }
+        None => {}
+    }
+
+    shards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_subscription;
+    use crate::WorldID;
+    use crate::realtime::event::EventNames;
+    use crate::realtime::subscription::{
+        CharacterSubscription, EventSubscription, SubscriptionSettings, WorldSubscription,
+    };
+
+    #[test]
+    fn characters_are_routed_to_their_shard() {
+        let subscription = SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![EventNames::Death])),
+            characters: Some(CharacterSubscription::Ids(vec![1, 2, 3, 4, 5])),
+            worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
+            ..SubscriptionSettings::empty()
+        };
+
+        let shards = split_subscription(&subscription, 2, false);
+
+        assert_eq!(
+            shards[0].characters,
+            Some(CharacterSubscription::Ids(vec![2, 4]))
+        );
+        assert_eq!(
+            shards[1].characters,
+            Some(CharacterSubscription::Ids(vec![1, 3, 5]))
+        );
+        assert_eq!(shards[0].event_names, shards[1].event_names);
+        assert_eq!(shards[0].worlds, subscription.worlds);
+        assert_eq!(shards[1].worlds, None);
+    }
+
+    #[test]
+    fn logical_and_keeps_worlds_on_every_shard() {
+        let subscription = SubscriptionSettings {
+            characters: Some(CharacterSubscription::Ids(vec![1, 2])),
+            worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
+            logical_and_characters_with_worlds: Some(true),
+            ..SubscriptionSettings::empty()
+        };
+
+        let shards = split_subscription(&subscription, 2, false);
+
+        assert!(
+            shards
+                .iter()
+                .all(|shard| shard.worlds == subscription.worlds)
+        );
+    }
+
+    #[test]
+    fn clearing_all_characters_reaches_every_shard() {
+        let subscription = SubscriptionSettings {
+            characters: Some(CharacterSubscription::All),
+            ..SubscriptionSettings::empty()
+        };
+
+        let subscribed = split_subscription(&subscription, 3, false);
+        let cleared = split_subscription(&subscription, 3, true);
+
+        assert_eq!(subscribed[0].characters, Some(CharacterSubscription::All));
+        assert_eq!(subscribed[1].characters, None);
+        assert!(
+            cleared
+                .iter()
+                .all(|shard| shard.characters == Some(CharacterSubscription::All))
+        );
+    }
+}
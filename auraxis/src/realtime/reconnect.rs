@@ -0,0 +1,142 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How the realtime client retries when the connection to Census is lost.
+///
+/// Delays grow exponentially from `initial_delay` by `multiplier` up to
+/// `max_delay`. Each delay is then randomly shortened by up to `jitter` (a
+/// fraction of the delay), so that many clients losing their connection at
+/// once do not all reconnect at the same moment, even once their delays have
+/// reached `max_delay`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    /// Number of retries before giving up, `None` retries forever.
+    pub max_attempts: Option<usize>,
+    /// How long a single connection attempt may take before it is abandoned.
+    pub connect_timeout: Duration,
+    /// Whether the initial connect in `RealtimeClient::connect` is retried
+    /// using this policy instead of failing on the first error.
+    ///
+    /// Off by default, so a wrong URL or a rejected service ID is reported by
+    /// `connect` right away. When enabled, `connect` only returns an error once
+    /// `max_attempts` retries have failed, and never with `max_attempts: None`.
+    pub retry_initial_connect: bool,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60 * 5),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+            connect_timeout: Duration::from_secs(10),
+            retry_initial_connect: false,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// The delay before each retry, with jitter applied.
+    pub fn delays(&self) -> Box<dyn Iterator<Item = Duration> + Send + Sync> {
+        let policy = self.clone();
+        let attempts = (0..).map(move |attempt| policy.delay(attempt));
+
+        match self.max_attempts {
+            Some(max_attempts) => Box::new(attempts.take(max_attempts)),
+            None => Box::new(attempts),
+        }
+    }
+
+    fn delay(&self, attempt: i32) -> Duration {
+        let max_delay = self.max_delay.as_secs_f64();
+        let delay =
+            (self.initial_delay.as_secs_f64() * self.multiplier.powi(attempt)).min(max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_unit();
+
+        // A NaN jitter makes the delay NaN, wait the longest.
+        Duration::try_from_secs_f64(delay * (1.0 - jitter)).unwrap_or(self.max_delay)
+    }
+}
+
+/// A random number in `[0, 1)`.
+///
+/// Reconnect jitter does not need a good source of randomness, every
+/// `RandomState` is seeded with fresh random keys which is plenty.
+fn random_unit() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReconnectPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn delays_back_off_exponentially_up_to_max() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.0,
+            max_attempts: Some(6),
+            ..ReconnectPolicy::default()
+        };
+
+        let delays = policy.delays().map(|d| d.as_secs()).collect::<Vec<_>>();
+
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.5,
+            max_attempts: Some(100),
+            ..ReconnectPolicy::default()
+        };
+
+        let delays = policy.delays().collect::<Vec<_>>();
+        for delay in &delays {
+            assert!(*delay >= Duration::from_secs(5));
+            assert!(*delay <= Duration::from_secs(10));
+        }
+
+        // Clients at the cap must still be spread out.
+        assert!(delays.iter().any(|delay| *delay < Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn invalid_factors_fall_back_to_max_delay() {
+        let policy = ReconnectPolicy {
+            multiplier: f64::NAN,
+            jitter: f64::NAN,
+            max_attempts: Some(3),
+            ..ReconnectPolicy::default()
+        };
+
+        for delay in policy.delays() {
+            assert_eq!(delay, policy.max_delay);
+        }
+    }
+
+    #[test]
+    fn unlimited_attempts_never_exhaust() {
+        let policy = ReconnectPolicy {
+            max_attempts: None,
+            ..ReconnectPolicy::default()
+        };
+
+        assert_eq!(policy.delays().take(1000).count(), 1000);
+    }
+}
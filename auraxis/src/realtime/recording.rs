@@ -0,0 +1,391 @@
+use crate::AuraxisError;
+use crate::realtime::event::Event;
+
+use chrono::{DateTime, Utc};
+use metrics::counter;
+use serde::{Deserialize, Serialize};
+use serde_with::{TimestampMilliSeconds, serde_as};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::mpsc::Receiver;
+use tracing::{debug, error, warn};
+
+/// One line of a recording: a raw `serviceMessage` payload and when it was
+/// received.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Recorded<T> {
+    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    received_at: DateTime<Utc>,
+    payload: T,
+}
+
+/// Records the raw ESS event payloads received by a client as NDJSON.
+///
+/// Set it as `recorder` in the client config. Payloads are recorded as sent
+/// by Census, before deduplication, so a recording can be replayed with
+/// [`Replay`] through the same `Event` deserialization. Writing happens on a
+/// background thread and never blocks the connection: at most `capacity`
+/// payloads wait for the writer, further ones are dropped and counted in
+/// `realtime_recorder_messages_dropped`.
+#[derive(Debug, Clone)]
+pub struct EventRecorder {
+    messages: mpsc::SyncSender<(DateTime<Utc>, String)>,
+}
+
+/// Payloads waiting to be written by default, a few seconds of a busy stream.
+const DEFAULT_RECORDER_CAPACITY: usize = 10_000;
+
+impl EventRecorder {
+    /// Record to a newly created file at `path`, truncating an existing one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, AuraxisError> {
+        let file = File::create(path).map_err(anyhow::Error::from)?;
+
+        Ok(Self::from_writer(file))
+    }
+
+    pub fn from_writer(writer: impl Write + Send + 'static) -> Self {
+        Self::with_capacity(writer, DEFAULT_RECORDER_CAPACITY)
+    }
+
+    /// Record to `writer`, keeping at most `capacity` payloads waiting for it.
+    pub fn with_capacity(writer: impl Write + Send + 'static, capacity: usize) -> Self {
+        let (messages_tx, messages_rx) =
+            mpsc::sync_channel::<(DateTime<Utc>, String)>(capacity.max(1));
+
+        std::thread::spawn(move || write_recording(BufWriter::new(writer), messages_rx));
+
+        Self {
+            messages: messages_tx,
+        }
+    }
+
+    /// Record `text` if it is a `serviceMessage`.
+    pub(crate) fn record(&self, text: &str) {
+        if !text.contains("serviceMessage") {
+            return;
+        }
+
+        match self.messages.try_send((Utc::now(), text.to_owned())) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => {
+                counter!("realtime_recorder_messages_dropped").increment(1);
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => {
+                debug!("Event recorder stopped, not recording message");
+            }
+        }
+    }
+}
+
+fn write_recording(
+    mut writer: BufWriter<impl Write>,
+    messages: mpsc::Receiver<(DateTime<Utc>, String)>,
+) {
+    let mut next = messages.recv().ok();
+
+    while let Some((received_at, text)) = next.take() {
+        if let Some(line) = recording_line(received_at, &text)
+            && let Err(err) = writeln!(writer, "{line}")
+        {
+            error!("Failed to write event recording: {err}");
+            return;
+        }
+
+        next = match messages.try_recv() {
+            Ok(message) => Some(message),
+            // Flush whenever we have caught up, so the file is usable while recording.
+            Err(mpsc::TryRecvError::Empty) => {
+                if let Err(err) = writer.flush() {
+                    error!("Failed to flush event recording: {err}");
+                    return;
+                }
+
+                messages.recv().ok()
+            }
+            Err(mpsc::TryRecvError::Disconnected) => None,
+        };
+    }
+
+    let _ = writer.flush();
+}
+
+fn recording_line(received_at: DateTime<Utc>, text: &str) -> Option<String> {
+    let mut message: serde_json::Value = serde_json::from_str(text).ok()?;
+    if message.get("type")?.as_str()? != "serviceMessage" {
+        return None;
+    }
+
+    let recorded = Recorded {
+        received_at,
+        payload: message.get_mut("payload")?.take(),
+    };
+
+    serde_json::to_string(&recorded).ok()
+}
+
+/// How fast a [`Replay`] delivers events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Keep the original spacing between events.
+    RealTime,
+    /// Divide the original spacing between events by this factor. Factors
+    /// that are not positive, or so small that the spacing overflows, replay
+    /// as fast as possible.
+    Accelerated(f64),
+    /// Deliver events as soon as the consumer takes them.
+    AsFastAsPossible,
+}
+
+/// Plays back a recording made by [`EventRecorder`].
+///
+/// Lines that cannot be parsed are logged and skipped.
+#[derive(Debug)]
+pub struct Replay<R> {
+    reader: R,
+    speed: ReplaySpeed,
+    capacity: usize,
+}
+
+impl Replay<BufReader<File>> {
+    /// Replay the recording at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AuraxisError> {
+        let file = File::open(path).map_err(anyhow::Error::from)?;
+
+        Ok(Self::new(BufReader::new(file)))
+    }
+}
+
+impl<R: BufRead + Send + 'static> Replay<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            speed: ReplaySpeed::AsFastAsPossible,
+            capacity: 1000,
+        }
+    }
+
+    #[must_use]
+    pub fn speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    #[must_use]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Start the replay on a background thread.
+    ///
+    /// The receiver behaves like the one returned by `RealtimeClient::connect`
+    /// and is closed once the recording has been played back.
+    pub fn start(self) -> Receiver<Event> {
+        let (events_tx, events_rx) = tokio::sync::mpsc::channel::<Event>(self.capacity);
+
+        std::thread::spawn(move || {
+            let mut previous: Option<DateTime<Utc>> = None;
+
+            for (index, line) in self.reader.lines().enumerate() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) => {
+                        error!("Failed to read event recording: {err}");
+                        return;
+                    }
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let recorded: Recorded<Event> = match serde_json::from_str(&line) {
+                    Ok(recorded) => recorded,
+                    Err(err) => {
+                        warn!("Skipping line {} of event recording: {err}", index + 1);
+                        continue;
+                    }
+                };
+
+                if let Some(previous) = previous
+                    && let Some(delay) = replay_delay(self.speed, previous, recorded.received_at)
+                {
+                    std::thread::sleep(delay);
+                }
+                previous = Some(recorded.received_at);
+
+                if events_tx.blocking_send(recorded.payload).is_err() {
+                    debug!("Replay consumer closed, stopping replay");
+                    return;
+                }
+            }
+        });
+
+        events_rx
+    }
+}
+
+fn replay_delay(
+    speed: ReplaySpeed,
+    previous: DateTime<Utc>,
+    current: DateTime<Utc>,
+) -> Option<Duration> {
+    let elapsed = (current - previous).to_std().ok()?;
+
+    match speed {
+        ReplaySpeed::RealTime => Some(elapsed),
+        ReplaySpeed::Accelerated(factor) if factor > 0.0 => {
+            Duration::try_from_secs_f64(elapsed.as_secs_f64() / factor).ok()
+        }
+        ReplaySpeed::Accelerated(_) | ReplaySpeed::AsFastAsPossible => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventRecorder, Replay, ReplaySpeed, recording_line, replay_delay};
+    use crate::realtime::event::Event;
+    use chrono::{TimeZone, Utc};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use std::io::{Cursor, Write};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    const LOGIN: &str = r#"{"payload":{"character_id":"5428010618035323201","event_name":"PlayerLogin","timestamp":"1700000000","world_id":"17"},"service":"event","type":"serviceMessage"}"#;
+
+    #[test]
+    fn recorded_messages_replay_as_events() {
+        let received_at = Utc.timestamp_opt(1700000000, 0).unwrap();
+        let line = recording_line(received_at, LOGIN).unwrap();
+
+        assert!(recording_line(received_at, r#"{"type":"heartbeat"}"#).is_none());
+
+        let recording = format!("{line}\nnot json\n{line}\n");
+        let mut events = Replay::new(Cursor::new(recording)).start();
+
+        for _ in 0..2 {
+            assert!(matches!(
+                events.blocking_recv(),
+                Some(Event::PlayerLogin(login)) if login.character_id == 5428010618035323201
+            ));
+        }
+        assert!(events.blocking_recv().is_none());
+    }
+
+    #[test]
+    fn replay_delay_follows_speed() {
+        let previous = Utc.timestamp_opt(1700000000, 0).unwrap();
+        let current = Utc.timestamp_opt(1700000010, 0).unwrap();
+
+        assert_eq!(
+            replay_delay(ReplaySpeed::RealTime, previous, current),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            replay_delay(ReplaySpeed::Accelerated(5.0), previous, current),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            replay_delay(ReplaySpeed::AsFastAsPossible, previous, current),
+            None
+        );
+        assert_eq!(replay_delay(ReplaySpeed::RealTime, current, previous), None);
+        assert_eq!(
+            replay_delay(
+                ReplaySpeed::Accelerated(f64::MIN_POSITIVE),
+                previous,
+                current
+            ),
+            None
+        );
+        assert_eq!(
+            replay_delay(ReplaySpeed::Accelerated(f64::NAN), previous, current),
+            None
+        );
+    }
+
+    /// A writer shared with the test.
+    #[derive(Clone, Default)]
+    struct Shared(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_every_message() {
+        let output = Shared::default();
+        let recorder = EventRecorder::from_writer(output.clone());
+
+        for _ in 0..100 {
+            recorder.record(LOGIN);
+        }
+        drop(recorder);
+
+        let lines = || line_count(&output.0.lock().unwrap());
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while lines() < 100 && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(lines(), 100);
+    }
+
+    fn line_count(output: &[u8]) -> usize {
+        output.iter().filter(|byte| **byte == b'\n').count()
+    }
+
+    /// A writer stuck until the sender of its gate is dropped.
+    struct Stuck(mpsc::Receiver<()>);
+
+    impl Write for Stuck {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let _ = self.0.recv();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn slow_writers_drop_recordings() {
+        let metrics = DebuggingRecorder::new();
+        let snapshotter = metrics.snapshotter();
+        let (gate, stuck) = mpsc::channel();
+        let recorder = EventRecorder::with_capacity(Stuck(stuck), 1);
+
+        metrics::with_local_recorder(&metrics, || {
+            for _ in 0..5 {
+                recorder.record(LOGIN);
+            }
+        });
+        drop(gate);
+
+        let dropped = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find(|(key, _, _, _)| key.key().name() == "realtime_recorder_messages_dropped")
+            .map(|(_, _, _, value)| value);
+        assert!(matches!(dropped, Some(DebugValue::Counter(count)) if count >= 3));
+    }
+}
@@ -2,11 +2,12 @@ use crate::realtime::utils::{
     serialize_all_subscription, serialize_char_ids_subscription, serialize_world_ids_subscription,
 };
 
-use crate::realtime::event::EventNames;
-use crate::realtime::Service;
-use crate::{CharacterID, WorldID};
+use crate::realtime::client::RealtimeClient;
+use crate::realtime::event::{Event, EventNames};
+use crate::realtime::{Service, Subscription};
+use crate::{AuraxisError, CharacterID, WorldID};
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 #[derive(Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(untagged)]
@@ -35,6 +36,18 @@ pub enum EventSubscription {
     Ids(Vec<EventNames>),
 }
 
+impl EventSubscription {
+    /// Whether this subscription delivers `event`.
+    pub fn matches(&self, event: &Event) -> bool {
+        match self {
+            EventSubscription::All => true,
+            EventSubscription::Ids(event_names) => {
+                event_names.iter().any(|name| name.matches(event))
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SubscriptionSettings {
@@ -62,6 +75,11 @@ impl Default for SubscriptionSettings {
 }
 
 impl SubscriptionSettings {
+    /// Build a validated subscription, see [`SubscriptionSettingsBuilder`].
+    pub fn builder() -> SubscriptionSettingsBuilder {
+        SubscriptionSettingsBuilder::default()
+    }
+
     pub fn empty() -> Self {
         Self {
             event_names: None,
@@ -89,6 +107,174 @@ impl SubscriptionSettings {
         self.service = other.service;
     }
 
+    /// How `ack` differs from this subscription, one description per field.
+    ///
+    /// The character count is not checked when subscribed to all characters.
+    pub(crate) fn ack_differences(&self, ack: &Subscription) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        compare_names(
+            "eventNames",
+            &self.event_name_strings(),
+            &ack.event_names,
+            &mut differences,
+        );
+        compare_names(
+            "worlds",
+            &self.world_strings(),
+            &ack.worlds,
+            &mut differences,
+        );
+
+        if let Some(character_count) = self.character_count()
+            && character_count != ack.character_count
+        {
+            differences.push(format!(
+                "characterCount is {} instead of {character_count}",
+                ack.character_count
+            ));
+        }
+
+        let logical_and = self.logical_and_characters_with_worlds.unwrap_or(false);
+        if logical_and != ack.logical_and_characters_with_worlds {
+            differences.push(format!(
+                "logicalAndCharactersWithWorlds is {} instead of {logical_and}",
+                ack.logical_and_characters_with_worlds
+            ));
+        }
+
+        differences
+    }
+
+    /// The entries to subscribe to and the entries to clear to turn `ack`
+    /// into this subscription.
+    ///
+    /// Census only reports how many characters are subscribed, so a wrong
+    /// count subscribes to every character again.
+    pub(crate) fn ack_corrections(&self, ack: &Subscription) -> (Self, Self) {
+        let mut subscribe = Self {
+            service: self.service.clone(),
+            ..Self::empty()
+        };
+        let mut clear = subscribe.clone();
+
+        let acknowledged = ack.event_names.iter().cloned().collect::<BTreeSet<_>>();
+        let missing = self
+            .event_name_strings()
+            .difference(&acknowledged)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        subscribe.event_names = match &self.event_names {
+            Some(EventSubscription::All) if !missing.is_empty() => Some(EventSubscription::All),
+            Some(EventSubscription::Ids(event_names)) if !missing.is_empty() => {
+                Some(EventSubscription::Ids(
+                    event_names
+                        .iter()
+                        .filter(|event_name| missing.contains(&event_name.to_string()))
+                        .cloned()
+                        .collect(),
+                ))
+            }
+            _ => None,
+        };
+        let unexpected = acknowledged
+            .difference(&self.event_name_strings())
+            .cloned()
+            .collect::<Vec<_>>();
+        clear.event_names = if unexpected.iter().any(|event_name| event_name == "all") {
+            Some(EventSubscription::All)
+        } else {
+            let event_names = unexpected
+                .iter()
+                .filter_map(|event_name| event_name.parse().ok())
+                .collect::<Vec<_>>();
+            (!event_names.is_empty()).then_some(EventSubscription::Ids(event_names))
+        };
+
+        let acknowledged = ack.worlds.iter().cloned().collect::<BTreeSet<_>>();
+        let missing = self
+            .world_strings()
+            .difference(&acknowledged)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        subscribe.worlds = match &self.worlds {
+            Some(WorldSubscription::All) if !missing.is_empty() => Some(WorldSubscription::All),
+            Some(WorldSubscription::Ids(worlds)) if !missing.is_empty() => {
+                Some(WorldSubscription::Ids(
+                    worlds
+                        .iter()
+                        .filter(|world| missing.contains(&u16::from(**world).to_string()))
+                        .copied()
+                        .collect(),
+                ))
+            }
+            _ => None,
+        };
+        let unexpected = acknowledged
+            .difference(&self.world_strings())
+            .cloned()
+            .collect::<Vec<_>>();
+        clear.worlds = if unexpected.iter().any(|world| world == "all") {
+            Some(WorldSubscription::All)
+        } else {
+            let worlds = unexpected
+                .iter()
+                .filter_map(|world| world.parse::<u16>().ok())
+                .map(WorldID::from)
+                .collect::<Vec<_>>();
+            (!worlds.is_empty()).then_some(WorldSubscription::Ids(worlds))
+        };
+
+        if self
+            .character_count()
+            .is_some_and(|character_count| character_count != ack.character_count)
+        {
+            subscribe.characters = self.characters.clone();
+        }
+
+        let logical_and = self.logical_and_characters_with_worlds.unwrap_or(false);
+        if logical_and != ack.logical_and_characters_with_worlds {
+            subscribe.logical_and_characters_with_worlds = Some(logical_and);
+        }
+
+        (subscribe, clear)
+    }
+
+    /// The event names as acknowledged by Census.
+    fn event_name_strings(&self) -> BTreeSet<String> {
+        match &self.event_names {
+            Some(EventSubscription::All) => BTreeSet::from([String::from("all")]),
+            Some(EventSubscription::Ids(event_names)) => {
+                event_names.iter().map(ToString::to_string).collect()
+            }
+            None => BTreeSet::new(),
+        }
+    }
+
+    /// The worlds as acknowledged by Census.
+    fn world_strings(&self) -> BTreeSet<String> {
+        match &self.worlds {
+            Some(WorldSubscription::All) => BTreeSet::from([String::from("all")]),
+            Some(WorldSubscription::Ids(worlds)) => worlds
+                .iter()
+                .map(|world| u16::from(*world).to_string())
+                .collect(),
+            None => BTreeSet::new(),
+        }
+    }
+
+    /// The character count Census should acknowledge, `None` when subscribed
+    /// to all characters.
+    fn character_count(&self) -> Option<u64> {
+        match &self.characters {
+            Some(CharacterSubscription::All) => None,
+            Some(CharacterSubscription::Ids(ids)) => {
+                Some(ids.iter().collect::<HashSet<_>>().len() as u64)
+            }
+            None => Some(0),
+        }
+    }
+
     pub fn clear(&mut self, other: &Self) {
         self.event_names =
             clear_event_subscription(self.event_names.take(), other.event_names.as_ref());
@@ -102,6 +288,579 @@ impl SubscriptionSettings {
     }
 }
 
+/// Builds [`SubscriptionSettings`] without spelling out every `Option`.
+///
+/// ```
+/// use auraxis::WorldID;
+/// use auraxis::realtime::event::EventNames;
+/// use auraxis::realtime::subscription::SubscriptionSettings;
+///
+/// let subscription = SubscriptionSettings::builder()
+///     .events([EventNames::Death, EventNames::PlayerLogin])
+///     .worlds([WorldID::Emerald])
+///     .all_characters()
+///     .logical_and(true)
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// Calling a list method more than once adds to the list, and the `all_*`
+/// methods take precedence over any list.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionSettingsBuilder {
+    event_names: Option<EventSubscription>,
+    characters: Option<CharacterSubscription>,
+    worlds: Option<WorldSubscription>,
+    logical_and_characters_with_worlds: Option<bool>,
+}
+
+impl SubscriptionSettingsBuilder {
+    #[must_use]
+    pub fn events(mut self, event_names: impl IntoIterator<Item = EventNames>) -> Self {
+        self.event_names = merge_event_subscription(
+            self.event_names.take(),
+            Some(EventSubscription::Ids(event_names.into_iter().collect())),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn all_events(mut self) -> Self {
+        self.event_names = Some(EventSubscription::All);
+        self
+    }
+
+    #[must_use]
+    pub fn characters(mut self, characters: impl IntoIterator<Item = CharacterID>) -> Self {
+        self.characters = merge_character_subscription(
+            self.characters.take(),
+            Some(CharacterSubscription::Ids(characters.into_iter().collect())),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn all_characters(mut self) -> Self {
+        self.characters = Some(CharacterSubscription::All);
+        self
+    }
+
+    #[must_use]
+    pub fn worlds(mut self, worlds: impl IntoIterator<Item = WorldID>) -> Self {
+        self.worlds = merge_world_subscription(
+            self.worlds.take(),
+            Some(WorldSubscription::Ids(worlds.into_iter().collect())),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn all_worlds(mut self) -> Self {
+        self.worlds = Some(WorldSubscription::All);
+        self
+    }
+
+    /// Only receive character events from the subscribed worlds, instead of
+    /// character events and world events independently.
+    #[must_use]
+    pub fn logical_and(mut self, logical_and: bool) -> Self {
+        self.logical_and_characters_with_worlds = Some(logical_and);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// This function will return an error if nothing was added, if a list was
+    /// given without any entries, or if `logical_and` was set without both
+    /// characters and worlds.
+    pub fn build(self) -> Result<SubscriptionSettings, AuraxisError> {
+        let subscription = SubscriptionSettings {
+            event_names: self.event_names,
+            characters: self.characters,
+            logical_and_characters_with_worlds: self.logical_and_characters_with_worlds,
+            worlds: self.worlds,
+            service: Service::Event,
+        };
+
+        if subscription.is_empty() {
+            return Err(invalid_subscription("Subscription is empty"));
+        }
+
+        if matches!(&subscription.event_names, Some(EventSubscription::Ids(ids)) if ids.is_empty())
+        {
+            return Err(invalid_subscription(
+                "Subscription has an empty list of events",
+            ));
+        }
+
+        if matches!(&subscription.characters, Some(CharacterSubscription::Ids(ids)) if ids.is_empty())
+        {
+            return Err(invalid_subscription(
+                "Subscription has an empty list of characters",
+            ));
+        }
+
+        if matches!(&subscription.worlds, Some(WorldSubscription::Ids(ids)) if ids.is_empty()) {
+            return Err(invalid_subscription(
+                "Subscription has an empty list of worlds",
+            ));
+        }
+
+        if subscription.logical_and_characters_with_worlds.is_some()
+            && (subscription.characters.is_none() || subscription.worlds.is_none())
+        {
+            return Err(invalid_subscription(
+                "logical_and requires both characters and worlds to be subscribed",
+            ));
+        }
+
+        Ok(subscription)
+    }
+}
+
+fn invalid_subscription(reason: &str) -> AuraxisError {
+    AuraxisError::InvalidSubscription {
+        reason: reason.to_string(),
+    }
+}
+
+/// The `subscribe` and `clearSubscribe` needed to change `current` into `target`.
+///
+/// Entries are only subscribed or cleared when they change. The subscribe has
+/// to be sent first so nothing is missed in between. Narrowing a subscription
+/// to all of something down to specific IDs clears the "all" entry, which
+/// requires subscribing to `target` again afterwards.
+pub(crate) fn subscription_diff(
+    current: &SubscriptionSettings,
+    target: &SubscriptionSettings,
+) -> (SubscriptionSettings, SubscriptionSettings) {
+    let mut subscribe = SubscriptionSettings::empty();
+    let mut clear = SubscriptionSettings::empty();
+
+    match (&current.event_names, &target.event_names) {
+        (Some(EventSubscription::All), Some(EventSubscription::All)) | (None, None) => {}
+        (_, Some(EventSubscription::All)) => subscribe.event_names = Some(EventSubscription::All),
+        (Some(EventSubscription::All), _) => clear.event_names = Some(EventSubscription::All),
+        (current, target) => {
+            let current = match current {
+                Some(EventSubscription::Ids(ids)) => ids.as_slice(),
+                _ => &[],
+            };
+            let target = match target {
+                Some(EventSubscription::Ids(ids)) => ids.as_slice(),
+                _ => &[],
+            };
+
+            let added = target
+                .iter()
+                .filter(|id| !current.contains(id))
+                .cloned()
+                .collect::<Vec<_>>();
+            let removed = current
+                .iter()
+                .filter(|id| !target.contains(id))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            subscribe.event_names = (!added.is_empty()).then_some(EventSubscription::Ids(added));
+            clear.event_names = (!removed.is_empty()).then_some(EventSubscription::Ids(removed));
+        }
+    }
+
+    match (&current.characters, &target.characters) {
+        (Some(CharacterSubscription::All), Some(CharacterSubscription::All)) | (None, None) => {}
+        (_, Some(CharacterSubscription::All)) => {
+            subscribe.characters = Some(CharacterSubscription::All)
+        }
+        (Some(CharacterSubscription::All), _) => {
+            clear.characters = Some(CharacterSubscription::All)
+        }
+        (current, target) => {
+            let (added, removed) = diff_ids(
+                match current {
+                    Some(CharacterSubscription::Ids(ids)) => ids,
+                    _ => &[],
+                },
+                match target {
+                    Some(CharacterSubscription::Ids(ids)) => ids,
+                    _ => &[],
+                },
+            );
+
+            subscribe.characters = (!added.is_empty()).then_some(CharacterSubscription::Ids(added));
+            clear.characters = (!removed.is_empty()).then_some(CharacterSubscription::Ids(removed));
+        }
+    }
+
+    match (&current.worlds, &target.worlds) {
+        (Some(WorldSubscription::All), Some(WorldSubscription::All)) | (None, None) => {}
+        (_, Some(WorldSubscription::All)) => subscribe.worlds = Some(WorldSubscription::All),
+        (Some(WorldSubscription::All), _) => clear.worlds = Some(WorldSubscription::All),
+        (current, target) => {
+            let (added, removed) = diff_ids(
+                match current {
+                    Some(WorldSubscription::Ids(ids)) => ids,
+                    _ => &[],
+                },
+                match target {
+                    Some(WorldSubscription::Ids(ids)) => ids,
+                    _ => &[],
+                },
+            );
+
+            subscribe.worlds = (!added.is_empty()).then_some(WorldSubscription::Ids(added));
+            clear.worlds = (!removed.is_empty()).then_some(WorldSubscription::Ids(removed));
+        }
+    }
+
+    let logical_and = target.logical_and_characters_with_worlds.unwrap_or(false);
+    if logical_and != current.logical_and_characters_with_worlds.unwrap_or(false) {
+        subscribe.logical_and_characters_with_worlds = Some(logical_and);
+    }
+
+    (subscribe, clear)
+}
+
+fn diff_ids<T: Copy + Eq + std::hash::Hash>(current: &[T], target: &[T]) -> (Vec<T>, Vec<T>) {
+    let current_ids = current.iter().collect::<HashSet<_>>();
+    let target_ids = target.iter().collect::<HashSet<_>>();
+
+    let added = target
+        .iter()
+        .filter(|id| !current_ids.contains(id))
+        .copied()
+        .collect();
+    let removed = current
+        .iter()
+        .filter(|id| !target_ids.contains(id))
+        .copied()
+        .collect();
+
+    (added, removed)
+}
+
+/// Keeps part of a client's subscription alive, see [`RealtimeClient::subscribe`].
+///
+/// Dropping or cancelling the handle clears the events, characters and
+/// worlds that no other handle still needs. The `logicalAndCharactersWithWorlds`
+/// flag applies to the whole connection and is left as is.
+#[must_use = "dropping a SubscriptionHandle clears its subscription"]
+#[derive(Debug)]
+pub struct SubscriptionHandle {
+    subscriptions: Vec<(RealtimeClient, SubscriptionSettings, u64)>,
+}
+
+impl SubscriptionHandle {
+    pub(crate) fn new(
+        client: RealtimeClient,
+        subscription: SubscriptionSettings,
+        generation: u64,
+    ) -> Self {
+        Self {
+            subscriptions: vec![(client, subscription, generation)],
+        }
+    }
+
+    /// One handle releasing every subscription held by `handles`.
+    pub(crate) fn join(handles: impl IntoIterator<Item = SubscriptionHandle>) -> Self {
+        let subscriptions = handles
+            .into_iter()
+            .flat_map(|mut handle| std::mem::take(&mut handle.subscriptions))
+            .collect();
+
+        Self { subscriptions }
+    }
+
+    /// Clear the entries of this subscription that no other handle needs.
+    /// Same as dropping the handle.
+    pub fn cancel(self) {}
+
+    /// Keep the subscription for as long as the client lives. It can still
+    /// be removed with `clear_subscribe` or `clear_all_subscriptions`.
+    pub fn detach(mut self) {
+        self.subscriptions.clear();
+    }
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        for (client, subscription, generation) in self.subscriptions.drain(..) {
+            client.release(&subscription, generation);
+        }
+    }
+}
+
+/// How many handles need each subscribed event, character and world.
+///
+/// Replacing the whole subscription starts a new generation, handles from
+/// earlier generations no longer release anything.
+#[derive(Debug, Default)]
+pub(crate) struct SubscriptionCounts {
+    generation: u64,
+    all_events: usize,
+    event_names: Vec<(EventNames, usize)>,
+    all_characters: usize,
+    characters: HashMap<CharacterID, usize>,
+    all_worlds: usize,
+    worlds: HashMap<WorldID, usize>,
+}
+
+impl SubscriptionCounts {
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Start a new generation holding only `subscription`.
+    pub(crate) fn reset(&mut self, subscription: &SubscriptionSettings) {
+        *self = Self {
+            generation: self.generation + 1,
+            ..Self::default()
+        };
+        self.add(subscription);
+    }
+
+    pub(crate) fn add(&mut self, subscription: &SubscriptionSettings) {
+        match &subscription.event_names {
+            Some(EventSubscription::All) => self.all_events += 1,
+            Some(EventSubscription::Ids(event_names)) => {
+                for event_name in event_names {
+                    match self
+                        .event_names
+                        .iter_mut()
+                        .find(|(counted, _)| counted == event_name)
+                    {
+                        Some((_, count)) => *count += 1,
+                        None => self.event_names.push((event_name.clone(), 1)),
+                    }
+                }
+            }
+            None => {}
+        }
+
+        match &subscription.characters {
+            Some(CharacterSubscription::All) => self.all_characters += 1,
+            Some(CharacterSubscription::Ids(ids)) => {
+                for id in ids {
+                    *self.characters.entry(*id).or_default() += 1;
+                }
+            }
+            None => {}
+        }
+
+        match &subscription.worlds {
+            Some(WorldSubscription::All) => self.all_worlds += 1,
+            Some(WorldSubscription::Ids(ids)) => {
+                for id in ids {
+                    *self.worlds.entry(*id).or_default() += 1;
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Drop one reference to every entry of `subscription`, returning the
+    /// entries that are no longer referenced at all. Entries still covered by
+    /// a subscription to all events, characters or worlds are not returned.
+    pub(crate) fn release(
+        &mut self,
+        subscription: &SubscriptionSettings,
+        generation: u64,
+    ) -> SubscriptionSettings {
+        let mut released = SubscriptionSettings::empty();
+        if generation != self.generation {
+            return released;
+        }
+
+        match &subscription.event_names {
+            Some(EventSubscription::All) => {
+                if release_count(&mut self.all_events) {
+                    released.event_names = Some(EventSubscription::All);
+                }
+            }
+            Some(EventSubscription::Ids(event_names)) => {
+                let mut released_names = Vec::new();
+                for event_name in event_names {
+                    if let Some(position) = self
+                        .event_names
+                        .iter()
+                        .position(|(counted, _)| counted == event_name)
+                        && release_count(&mut self.event_names[position].1)
+                    {
+                        self.event_names.remove(position);
+                        released_names.push(event_name.clone());
+                    }
+                }
+                if !released_names.is_empty() && self.all_events == 0 {
+                    released.event_names = Some(EventSubscription::Ids(released_names));
+                }
+            }
+            None => {}
+        }
+
+        match &subscription.characters {
+            Some(CharacterSubscription::All) => {
+                if release_count(&mut self.all_characters) {
+                    released.characters = Some(CharacterSubscription::All);
+                }
+            }
+            Some(CharacterSubscription::Ids(ids)) => {
+                let released_ids = release_ids(&mut self.characters, ids);
+                if !released_ids.is_empty() && self.all_characters == 0 {
+                    released.characters = Some(CharacterSubscription::Ids(released_ids));
+                }
+            }
+            None => {}
+        }
+
+        match &subscription.worlds {
+            Some(WorldSubscription::All) => {
+                if release_count(&mut self.all_worlds) {
+                    released.worlds = Some(WorldSubscription::All);
+                }
+            }
+            Some(WorldSubscription::Ids(ids)) => {
+                let released_ids = release_ids(&mut self.worlds, ids);
+                if !released_ids.is_empty() && self.all_worlds == 0 {
+                    released.worlds = Some(WorldSubscription::Ids(released_ids));
+                }
+            }
+            None => {}
+        }
+
+        released
+    }
+
+    /// Stop counting the entries of `subscription`, which has been cleared
+    /// regardless of the handles still holding it.
+    pub(crate) fn forget(&mut self, subscription: &SubscriptionSettings) {
+        match &subscription.event_names {
+            Some(EventSubscription::All) => {
+                self.all_events = 0;
+                self.event_names.clear();
+            }
+            Some(EventSubscription::Ids(event_names)) => self
+                .event_names
+                .retain(|(counted, _)| !event_names.contains(counted)),
+            None => {}
+        }
+
+        match &subscription.characters {
+            Some(CharacterSubscription::All) => {
+                self.all_characters = 0;
+                self.characters.clear();
+            }
+            Some(CharacterSubscription::Ids(ids)) => {
+                for id in ids {
+                    self.characters.remove(id);
+                }
+            }
+            None => {}
+        }
+
+        match &subscription.worlds {
+            Some(WorldSubscription::All) => {
+                self.all_worlds = 0;
+                self.worlds.clear();
+            }
+            Some(WorldSubscription::Ids(ids)) => {
+                for id in ids {
+                    self.worlds.remove(id);
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// The subscription covering every counted entry.
+    pub(crate) fn subscription(&self) -> SubscriptionSettings {
+        let event_names = if self.all_events > 0 {
+            Some(EventSubscription::All)
+        } else if !self.event_names.is_empty() {
+            Some(EventSubscription::Ids(
+                self.event_names
+                    .iter()
+                    .map(|(event_name, _)| event_name.clone())
+                    .collect(),
+            ))
+        } else {
+            None
+        };
+
+        let characters = if self.all_characters > 0 {
+            Some(CharacterSubscription::All)
+        } else if !self.characters.is_empty() {
+            let mut ids = self.characters.keys().copied().collect::<Vec<_>>();
+            ids.sort_unstable();
+            Some(CharacterSubscription::Ids(ids))
+        } else {
+            None
+        };
+
+        let worlds = if self.all_worlds > 0 {
+            Some(WorldSubscription::All)
+        } else if !self.worlds.is_empty() {
+            let mut ids = self.worlds.keys().copied().collect::<Vec<_>>();
+            ids.sort_unstable_by_key(|id| u16::from(*id));
+            Some(WorldSubscription::Ids(ids))
+        } else {
+            None
+        };
+
+        SubscriptionSettings {
+            event_names,
+            characters,
+            worlds,
+            ..SubscriptionSettings::empty()
+        }
+    }
+}
+
+/// Decrement `count`, returning whether this released the last reference.
+fn release_count(count: &mut usize) -> bool {
+    if *count == 0 {
+        return false;
+    }
+
+    *count -= 1;
+    *count == 0
+}
+
+fn release_ids<T: Copy + Eq + std::hash::Hash>(
+    counts: &mut HashMap<T, usize>,
+    ids: &[T],
+) -> Vec<T> {
+    let mut released = Vec::new();
+    for id in ids {
+        if let Some(count) = counts.get_mut(id)
+            && release_count(count)
+        {
+            counts.remove(id);
+            released.push(*id);
+        }
+    }
+
+    released
+}
+
+fn compare_names(
+    field: &str,
+    expected: &BTreeSet<String>,
+    acknowledged: &[String],
+    differences: &mut Vec<String>,
+) {
+    let acknowledged = acknowledged.iter().cloned().collect::<BTreeSet<_>>();
+
+    let missing = expected.difference(&acknowledged).collect::<Vec<_>>();
+    if !missing.is_empty() {
+        differences.push(format!("{field} is missing {missing:?}"));
+    }
+
+    let unexpected = acknowledged.difference(expected).collect::<Vec<_>>();
+    if !unexpected.is_empty() {
+        differences.push(format!("{field} unexpectedly contains {unexpected:?}"));
+    }
+}
+
 fn merge_event_subscription(
     current: Option<EventSubscription>,
     update: Option<EventSubscription>,
@@ -111,8 +870,12 @@ fn merge_event_subscription(
             Some(EventSubscription::All)
         }
         (Some(EventSubscription::Ids(mut current)), Some(EventSubscription::Ids(update))) => {
-            current.extend(update);
-            current.dedup();
+            let mut seen = current.iter().cloned().collect::<HashSet<_>>();
+            for event_name in update {
+                if seen.insert(event_name.clone()) {
+                    current.push(event_name);
+                }
+            }
             Some(EventSubscription::Ids(current))
         }
         (None, Some(update)) | (Some(update), None) => Some(update),
@@ -239,10 +1002,61 @@ fn clear_world_subscription(
 #[cfg(test)]
 mod tests {
     use super::{
-        CharacterSubscription, EventSubscription, SubscriptionSettings, WorldSubscription,
+        CharacterSubscription, EventSubscription, SubscriptionCounts, SubscriptionSettings,
+        WorldSubscription, subscription_diff,
     };
+    use crate::realtime::Subscription;
     use crate::realtime::event::EventNames;
-    use crate::WorldID;
+    use crate::{AuraxisError, WorldID};
+
+    #[test]
+    fn builder_builds_valid_subscriptions() {
+        let subscription = SubscriptionSettings::builder()
+            .events([EventNames::Death])
+            .events([EventNames::PlayerLogin, EventNames::Death])
+            .worlds([WorldID::Emerald])
+            .all_characters()
+            .logical_and(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            subscription,
+            SubscriptionSettings {
+                event_names: Some(EventSubscription::Ids(vec![
+                    EventNames::Death,
+                    EventNames::PlayerLogin,
+                ])),
+                characters: Some(CharacterSubscription::All),
+                logical_and_characters_with_worlds: Some(true),
+                worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
+                ..SubscriptionSettings::empty()
+            }
+        );
+    }
+
+    #[test]
+    fn builder_rejects_invalid_subscriptions() {
+        assert!(matches!(
+            SubscriptionSettings::builder().build(),
+            Err(AuraxisError::InvalidSubscription { .. })
+        ));
+        assert!(matches!(
+            SubscriptionSettings::builder()
+                .events([])
+                .all_worlds()
+                .build(),
+            Err(AuraxisError::InvalidSubscription { .. })
+        ));
+        assert!(matches!(
+            SubscriptionSettings::builder()
+                .all_events()
+                .all_worlds()
+                .logical_and(true)
+                .build(),
+            Err(AuraxisError::InvalidSubscription { .. })
+        ));
+    }
 
     #[test]
     fn merge_is_additive() {
@@ -296,4 +1110,180 @@ mod tests {
         );
         assert_eq!(subscription.logical_and_characters_with_worlds, None);
     }
+
+    #[test]
+    fn ack_corrections_only_touch_differing_entries() {
+        let subscription = SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![
+                EventNames::Death,
+                EventNames::PlayerLogin,
+            ])),
+            characters: Some(CharacterSubscription::Ids(vec![1, 2])),
+            worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
+            ..SubscriptionSettings::empty()
+        };
+        let ack = Subscription {
+            character_count: 2,
+            event_names: vec![String::from("Death")],
+            logical_and_characters_with_worlds: false,
+            worlds: vec![String::from("1"), String::from("17")],
+        };
+
+        let (subscribe, clear) = subscription.ack_corrections(&ack);
+        assert_eq!(
+            subscribe,
+            SubscriptionSettings {
+                event_names: Some(EventSubscription::Ids(vec![EventNames::PlayerLogin])),
+                ..SubscriptionSettings::empty()
+            }
+        );
+        assert_eq!(
+            clear,
+            SubscriptionSettings {
+                worlds: Some(WorldSubscription::Ids(vec![WorldID::Connery])),
+                ..SubscriptionSettings::empty()
+            }
+        );
+    }
+
+    #[test]
+    fn ack_differences_report_dropped_entries() {
+        let subscription = SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![
+                EventNames::Death,
+                EventNames::GainExperienceId(7),
+            ])),
+            characters: Some(CharacterSubscription::Ids(vec![1, 2, 2])),
+            worlds: Some(WorldSubscription::Ids(vec![WorldID::Emerald])),
+            ..SubscriptionSettings::empty()
+        };
+        let mut ack = Subscription {
+            character_count: 2,
+            event_names: vec![
+                String::from("Death"),
+                String::from("GainExperience_experience_id_7"),
+            ],
+            logical_and_characters_with_worlds: false,
+            worlds: vec![String::from("17")],
+        };
+
+        assert!(subscription.ack_differences(&ack).is_empty());
+
+        ack.event_names.pop();
+        ack.character_count = 1;
+        assert_eq!(
+            subscription.ack_differences(&ack),
+            vec![
+                String::from("eventNames is missing [\"GainExperience_experience_id_7\"]"),
+                String::from("characterCount is 1 instead of 2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_release_entries_nobody_needs() {
+        let kill_feed = SubscriptionSettings::builder()
+            .events([EventNames::Death])
+            .characters([1, 2])
+            .build()
+            .unwrap();
+        let tracker = SubscriptionSettings::builder()
+            .events([EventNames::Death, EventNames::PlayerLogin])
+            .characters([2])
+            .build()
+            .unwrap();
+
+        let mut counts = SubscriptionCounts::default();
+        counts.add(&kill_feed);
+        counts.add(&tracker);
+
+        let released = counts.release(&kill_feed, 0);
+        assert_eq!(released.event_names, None);
+        assert_eq!(
+            released.characters,
+            Some(CharacterSubscription::Ids(vec![1]))
+        );
+        assert_eq!(
+            counts.subscription().event_names,
+            Some(EventSubscription::Ids(vec![
+                EventNames::Death,
+                EventNames::PlayerLogin
+            ]))
+        );
+
+        let released = counts.release(&tracker, 0);
+        assert_eq!(
+            released.event_names,
+            Some(EventSubscription::Ids(vec![
+                EventNames::Death,
+                EventNames::PlayerLogin
+            ]))
+        );
+        assert!(counts.subscription().is_empty());
+    }
+
+    #[test]
+    fn counts_keep_entries_covered_by_all() {
+        let all = SubscriptionSettings::builder()
+            .all_events()
+            .all_worlds()
+            .build()
+            .unwrap();
+        let emerald = SubscriptionSettings::builder()
+            .events([EventNames::FacilityControl])
+            .worlds([WorldID::Emerald])
+            .build()
+            .unwrap();
+
+        let mut counts = SubscriptionCounts::default();
+        counts.add(&all);
+        counts.add(&emerald);
+
+        assert!(counts.release(&emerald, 0).is_empty());
+        counts.add(&emerald);
+
+        let released = counts.release(&all, 0);
+        assert_eq!(released.event_names, Some(EventSubscription::All));
+        assert_eq!(released.worlds, Some(WorldSubscription::All));
+        assert_eq!(counts.subscription().event_names, emerald.event_names);
+        assert_eq!(counts.subscription().worlds, emerald.worlds);
+    }
+
+    #[test]
+    fn diff_only_contains_changes() {
+        let current = SubscriptionSettings::builder()
+            .events([EventNames::Death])
+            .characters([1, 2, 3])
+            .all_worlds()
+            .build()
+            .unwrap();
+        let target = SubscriptionSettings::builder()
+            .events([EventNames::Death])
+            .characters([2, 3, 4])
+            .worlds([WorldID::Emerald])
+            .build()
+            .unwrap();
+
+        let (subscribe, clear) = subscription_diff(&current, &target);
+
+        assert_eq!(
+            subscribe,
+            SubscriptionSettings {
+                characters: Some(CharacterSubscription::Ids(vec![4])),
+                ..SubscriptionSettings::empty()
+            }
+        );
+        assert_eq!(
+            clear,
+            SubscriptionSettings {
+                characters: Some(CharacterSubscription::Ids(vec![1])),
+                worlds: Some(WorldSubscription::All),
+                ..SubscriptionSettings::empty()
+            }
+        );
+
+        let (subscribe, clear) = subscription_diff(&target, &target);
+        assert!(subscribe.is_empty());
+        assert!(clear.is_empty());
+    }
 }
@@ -1,9 +1,10 @@
-use crate::constants::WorldID;
 use crate::CharacterID;
+use crate::constants::WorldID;
 
 use chrono::Duration;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::json;
+use serde_with::{DeserializeAs, SerializeAs};
 
 pub fn serialize_optional_bool<S>(value: &Option<bool>, serializer: S) -> Result<S::Ok, S::Error>
 where
@@ -49,7 +50,7 @@ where
 {
     let mut ids = Vec::with_capacity(value.len());
     for id in value.iter() {
-        ids.push((*id as u8).to_string());
+        ids.push(u16::from(*id).to_string());
     }
 
     serializer.collect_seq(ids.iter())
@@ -77,11 +78,75 @@ where
     Ok(Duration::seconds(duration))
 }
 
-pub fn serialize_duration<S>(duration: &Duration, s: S) -> Result<S::Ok, S::Error>
+pub fn serialize_duration_to_str<S>(duration: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.collect_str(&duration.num_seconds())
+}
+
+/// A `Loadout`, `Faction` or `WorldID` as its numeric id string, for use with
+/// `#[serde_as]`.
+pub struct IdFromStr;
+
+impl<T> SerializeAs<T> for IdFromStr
+where
+    T: Copy + Into<u16>,
+{
+    fn serialize_as<S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&(*value).into())
+    }
+}
+
+impl<'de, T> DeserializeAs<'de, T> for IdFromStr
+where
+    T: From<u16>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let id: u16 = deserialize_from_str(deserializer)?;
+
+        Ok(T::from(id))
+    }
+}
+
+pub fn deserialize_unknown_event<'de, D>(
+    deserializer: D,
+) -> Result<(String, serde_json::Value), D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = serde_json::Value::deserialize(deserializer)?;
+    let event_name = raw
+        .get("event_name")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| serde::de::Error::missing_field("event_name"))?
+        .to_string();
+
+    Ok((event_name, raw))
+}
+
+pub fn serialize_unknown_event<S>(
+    _event_name: &str,
+    raw: &serde_json::Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    raw.serialize(serializer)
+}
+
+pub fn ser_bool_to_str_int<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    s.serialize_i64(duration.num_seconds())
+    serializer.serialize_str(if *value { "1" } else { "0" })
 }
 
 pub fn de_bool_from_str_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
@@ -0,0 +1,208 @@
+use crate::realtime::event::EventNames;
+use crate::realtime::subscription::{
+    CharacterSubscription, EventSubscription, SubscriptionSettings,
+};
+
+use futures_util::task::AtomicWaker;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::Context;
+use std::time::{Duration, Instant};
+
+/// Detection of connections that are still open but no longer deliver data.
+///
+/// When either check trips, the connection is dropped and re-established with
+/// the client's `ReconnectPolicy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchdogConfig {
+    /// Reconnect if no pong has been received for this long.
+    pub pong_timeout: Option<Duration>,
+    /// Reconnect if no event has been received for this long while subscribed
+    /// to events that are expected to arrive constantly, such as `Death` or
+    /// `GainExperience` for all characters.
+    pub silence_timeout: Option<Duration>,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            pong_timeout: Some(Duration::from_secs(30)),
+            silence_timeout: None,
+        }
+    }
+}
+
+/// Why the watchdog gave up on a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Staleness {
+    PongTimeout,
+    Silence,
+}
+
+impl Staleness {
+    pub(crate) fn reason(&self) -> &'static str {
+        match self {
+            Staleness::PongTimeout => "No pong received from Census",
+            Staleness::Silence => "No events received from Census",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Watchdog {
+    last_pong: Mutex<Instant>,
+    last_event: Mutex<Instant>,
+    stale: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl Watchdog {
+    pub(crate) fn new() -> Self {
+        Self {
+            last_pong: Mutex::new(Instant::now()),
+            last_event: Mutex::new(Instant::now()),
+            stale: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    /// A new connection has been established, start watching it from scratch.
+    pub(crate) fn reset(&self) {
+        let now = Instant::now();
+        *self.last_pong.lock().expect("watchdog state poisoned") = now;
+        *self.last_event.lock().expect("watchdog state poisoned") = now;
+        self.stale.store(false, Ordering::SeqCst);
+    }
+
+    pub(crate) fn pong(&self) {
+        *self.last_pong.lock().expect("watchdog state poisoned") = Instant::now();
+    }
+
+    pub(crate) fn event(&self) {
+        *self.last_event.lock().expect("watchdog state poisoned") = Instant::now();
+    }
+
+    pub(crate) fn check(
+        &self,
+        config: &WatchdogConfig,
+        subscription: &SubscriptionSettings,
+    ) -> Option<Staleness> {
+        if let Some(pong_timeout) = config.pong_timeout
+            && elapsed(&self.last_pong) > pong_timeout
+        {
+            return Some(Staleness::PongTimeout);
+        }
+
+        if let Some(silence_timeout) = config.silence_timeout
+            && is_high_volume(subscription)
+            && elapsed(&self.last_event) > silence_timeout
+        {
+            return Some(Staleness::Silence);
+        }
+
+        None
+    }
+
+    /// Mark the current connection as stale. The websocket ends its stream the
+    /// next time it is polled, which makes the reconnect stream reconnect.
+    pub(crate) fn trip(&self) {
+        let now = Instant::now();
+        *self.last_pong.lock().expect("watchdog state poisoned") = now;
+        *self.last_event.lock().expect("watchdog state poisoned") = now;
+        self.stale.store(true, Ordering::SeqCst);
+        self.waker.wake();
+    }
+
+    pub(crate) fn poll_stale(&self, cx: &mut Context<'_>) -> bool {
+        self.waker.register(cx.waker());
+        self.stale.swap(false, Ordering::SeqCst)
+    }
+}
+
+fn elapsed(instant: &Mutex<Instant>) -> Duration {
+    instant.lock().expect("watchdog state poisoned").elapsed()
+}
+
+/// Whether the subscription should produce a steady flow of events.
+fn is_high_volume(subscription: &SubscriptionSettings) -> bool {
+    let all_characters = matches!(subscription.characters, Some(CharacterSubscription::All));
+    let high_volume_events = match &subscription.event_names {
+        Some(EventSubscription::All) => true,
+        Some(EventSubscription::Ids(events)) => events.iter().any(|event| {
+            matches!(
+                event,
+                EventNames::Death
+                    | EventNames::GainExperience
+                    | EventNames::GainExperienceId(_)
+                    | EventNames::VehicleDestroy
+            )
+        }),
+        None => false,
+    };
+
+    all_characters && high_volume_events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Staleness, Watchdog, WatchdogConfig, is_high_volume};
+    use crate::realtime::event::EventNames;
+    use crate::realtime::subscription::{
+        CharacterSubscription, EventSubscription, SubscriptionSettings,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn only_constant_subscriptions_are_high_volume() {
+        let deaths = SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![EventNames::Death])),
+            characters: Some(CharacterSubscription::All),
+            ..SubscriptionSettings::empty()
+        };
+        let logins = SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![EventNames::PlayerLogin])),
+            characters: Some(CharacterSubscription::All),
+            ..SubscriptionSettings::empty()
+        };
+        let tracked_deaths = SubscriptionSettings {
+            event_names: Some(EventSubscription::Ids(vec![EventNames::Death])),
+            characters: Some(CharacterSubscription::Ids(vec![1])),
+            ..SubscriptionSettings::empty()
+        };
+
+        assert!(is_high_volume(&deaths));
+        assert!(!is_high_volume(&logins));
+        assert!(!is_high_volume(&tracked_deaths));
+    }
+
+    #[test]
+    fn check_reports_missing_pongs_and_silence() {
+        let watchdog = Watchdog::new();
+        let subscription = SubscriptionSettings::default();
+        let config = WatchdogConfig {
+            pong_timeout: Some(Duration::ZERO),
+            silence_timeout: Some(Duration::ZERO),
+        };
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(
+            watchdog.check(&config, &subscription),
+            Some(Staleness::PongTimeout)
+        );
+
+        let config = WatchdogConfig {
+            pong_timeout: None,
+            ..config
+        };
+        assert_eq!(
+            watchdog.check(&config, &subscription),
+            Some(Staleness::Silence)
+        );
+
+        let config = WatchdogConfig {
+            pong_timeout: Some(Duration::from_secs(60)),
+            silence_timeout: Some(Duration::from_secs(60)),
+        };
+        assert_eq!(watchdog.check(&config, &subscription), None);
+    }
+}
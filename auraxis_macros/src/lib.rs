@@ -71,7 +71,7 @@ pub fn derive_census_query(input: TokenStream) -> TokenStream {
                                             proc_macro::Level::Error,
                                             format!(
                                                 "{} is not a valid field identifier",
-                                                &main_field_name
+                                                main_field_name
                                             ),
                                         )
                                             .emit();